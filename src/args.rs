@@ -1,7 +1,8 @@
 use proc_macro2::{Group, Span};
 use syn::parse::{Error, Parse, ParseStream, Result};
-use syn::token::{Comma, Eq, Pub};
-use syn::{parse2, Ident, LitStr, Meta, Visibility};
+use syn::punctuated::Punctuated;
+use syn::token::{Comma, Eq, Paren, Pub, Ref};
+use syn::{parenthesized, parse2, Ident, LitStr, Meta, Path, Type, Visibility};
 
 mod kw {
     // NOTE: when adding new keywords update ArgList::next_is_kw
@@ -12,10 +13,48 @@ mod kw {
     syn::custom_keyword!(field_doc);
     syn::custom_keyword!(field_attrs);
     syn::custom_keyword!(from);
+    syn::custom_keyword!(three_way);
+    syn::custom_keyword!(fill_fn);
+    syn::custom_keyword!(merge_ref_fn);
+    syn::custom_keyword!(with_fn);
+    syn::custom_keyword!(merge_arc_fn);
+    syn::custom_keyword!(try_merge_fn);
+    syn::custom_keyword!(validate);
+    syn::custom_keyword!(validate_error);
+    syn::custom_keyword!(field_enum);
+    syn::custom_keyword!(update_enum);
+    syn::custom_keyword!(str_access);
+    syn::custom_keyword!(from_env);
+    syn::custom_keyword!(from_args);
+    syn::custom_keyword!(query);
+    syn::custom_keyword!(sql);
+    syn::custom_keyword!(matches);
+    syn::custom_keyword!(field_mask);
+    syn::custom_keyword!(roles);
+    syn::custom_keyword!(versions);
+    syn::custom_keyword!(debug);
+    syn::custom_keyword!(default);
+    syn::custom_keyword!(views);
+    syn::custom_keyword!(owned);
+    syn::custom_keyword!(unused_generics);
 
     pub mod attrs_sub {
         syn::custom_keyword!(add);
     }
+
+    pub mod from_sub {
+        syn::custom_keyword!(owned);
+    }
+
+    pub mod sql_sub {
+        syn::custom_keyword!(question);
+        syn::custom_keyword!(numbered);
+    }
+
+    pub mod unused_generics_sub {
+        syn::custom_keyword!(prune);
+        syn::custom_keyword!(phantom);
+    }
 }
 
 #[cfg_attr(test, derive(PartialEq))]
@@ -28,6 +67,31 @@ pub struct Args {
     pub field_doc: bool,
     pub field_attrs: Option<Attrs>,
     pub from: bool,
+    pub from_ref: bool,
+    pub three_way: Option<MergeFn>,
+    pub fill: Option<MergeFn>,
+    pub merge_ref: Option<MergeFn>,
+    pub with: Option<MergeFn>,
+    pub merge_arc: Option<MergeFn>,
+    pub try_merge: Option<MergeFn>,
+    pub validate: Option<Path>,
+    pub validate_error: Option<Type>,
+    pub field_enum: bool,
+    pub update_enum: Option<Vec<Meta>>,
+    pub str_access: bool,
+    pub from_env: bool,
+    pub from_args: bool,
+    pub query: bool,
+    pub sql: Option<Placeholder>,
+    pub matches: bool,
+    pub field_mask: bool,
+    pub roles: Option<Type>,
+    pub versions: bool,
+    pub debug: bool,
+    pub default: bool,
+    pub views: bool,
+    pub owned: bool,
+    pub unused_generics: Option<UnusedGenerics>,
 }
 
 enum Arg {
@@ -38,6 +102,31 @@ enum Arg {
     FieldDocs(bool),
     FieldAttrs(Attrs),
     From(bool),
+    FromRef(bool),
+    ThreeWay(MergeFn),
+    Fill(MergeFn),
+    MergeRef(MergeFn),
+    With(MergeFn),
+    MergeArc(MergeFn),
+    TryMerge(MergeFn),
+    Validate(Path),
+    ValidateError(Type),
+    FieldEnum(bool),
+    UpdateEnum(Vec<Meta>),
+    StrAccess(bool),
+    FromEnv(bool),
+    FromArgs(bool),
+    Query(bool),
+    Sql(Placeholder),
+    Matches(bool),
+    FieldMask(bool),
+    Roles(Type),
+    Versions(bool),
+    Debug(bool),
+    Default(bool),
+    Views(bool),
+    Owned(bool),
+    UnusedGenerics(UnusedGenerics),
 }
 
 #[cfg_attr(test, derive(PartialEq))]
@@ -77,6 +166,33 @@ pub enum Attrs {
 #[derive(Debug)]
 pub struct AttrList(Vec<Meta>);
 
+/// Kind of `From` implementation given to the `from` argument.
+#[derive(PartialEq)]
+enum FromKind {
+    /// `From<Original>`
+    Owned,
+    /// `From<&Original>`
+    Ref,
+}
+
+/// Placeholder style of generated SQL statements.
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum Placeholder {
+    /// `?`
+    Question,
+    /// `$1`, `$2`...
+    Numbered,
+}
+
+/// What to do with generic parameters no opt struct field uses.
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum UnusedGenerics {
+    /// Remove them from the opt struct.
+    Prune,
+    /// Keep them with a `PhantomData` field.
+    Phantom,
+}
+
 /// Parser for unordered args.
 struct ArgList {
     item: GenItem,
@@ -87,6 +203,30 @@ struct ArgList {
     field_doc: Option<Span>,
     field_attrs: Option<Span>,
     from: Option<Span>,
+    three_way: Option<Span>,
+    fill: Option<Span>,
+    merge_ref: Option<Span>,
+    with: Option<Span>,
+    merge_arc: Option<Span>,
+    try_merge: Option<Span>,
+    validate: Option<Span>,
+    validate_error: Option<Span>,
+    field_enum: Option<Span>,
+    update_enum: Option<Span>,
+    str_access: Option<Span>,
+    from_env: Option<Span>,
+    from_args: Option<Span>,
+    query: Option<Span>,
+    sql: Option<Span>,
+    matches: Option<Span>,
+    field_mask: Option<Span>,
+    roles: Option<Span>,
+    versions: Option<Span>,
+    debug: Option<Span>,
+    default: Option<Span>,
+    views: Option<Span>,
+    owned: Option<Span>,
+    unused_generics: Option<Span>,
     list: Vec<Arg>,
 }
 
@@ -135,11 +275,61 @@ impl Parse for ArgList {
                 arg_list.parse_field_attrs(input)?;
             } else if lookahead.peek(kw::from) {
                 arg_list.parse_from(input)?;
+            } else if lookahead.peek(kw::three_way) {
+                arg_list.parse_three_way(input)?;
+            } else if lookahead.peek(kw::fill_fn) {
+                arg_list.parse_fill(input)?;
+            } else if lookahead.peek(kw::merge_ref_fn) {
+                arg_list.parse_merge_ref(input)?;
+            } else if lookahead.peek(kw::with_fn) {
+                arg_list.parse_with(input)?;
+            } else if lookahead.peek(kw::merge_arc_fn) {
+                arg_list.parse_merge_arc(input)?;
+            } else if lookahead.peek(kw::try_merge_fn) {
+                arg_list.parse_try_merge(input)?;
+            } else if lookahead.peek(kw::validate) {
+                arg_list.parse_validate(input)?;
+            } else if lookahead.peek(kw::validate_error) {
+                arg_list.parse_validate_error(input)?;
+            } else if lookahead.peek(kw::field_enum) {
+                arg_list.parse_field_enum(input)?;
+            } else if lookahead.peek(kw::update_enum) {
+                arg_list.parse_update_enum(input)?;
+            } else if lookahead.peek(kw::str_access) {
+                arg_list.parse_str_access(input)?;
+            } else if lookahead.peek(kw::from_env) {
+                arg_list.parse_from_env(input)?;
+            } else if lookahead.peek(kw::from_args) {
+                arg_list.parse_from_args(input)?;
+            } else if lookahead.peek(kw::query) {
+                arg_list.parse_query(input)?;
+            } else if lookahead.peek(kw::sql) {
+                arg_list.parse_sql(input)?;
+            } else if lookahead.peek(kw::matches) {
+                arg_list.parse_matches(input)?;
+            } else if lookahead.peek(kw::field_mask) {
+                arg_list.parse_field_mask(input)?;
+            } else if lookahead.peek(kw::roles) {
+                arg_list.parse_roles(input)?;
+            } else if lookahead.peek(kw::versions) {
+                arg_list.parse_versions(input)?;
+            } else if lookahead.peek(kw::debug) {
+                arg_list.parse_debug(input)?;
+            } else if lookahead.peek(kw::default) {
+                arg_list.parse_default(input)?;
+            } else if lookahead.peek(kw::views) {
+                arg_list.parse_views(input)?;
+            } else if lookahead.peek(kw::owned) {
+                arg_list.parse_owned(input)?;
+            } else if lookahead.peek(kw::unused_generics) {
+                arg_list.parse_unused_generics(input)?;
             } else {
                 return Err(lookahead.error());
             }
         }
 
+        arg_list.check_dependencies()?;
+
         Ok(arg_list)
     }
 }
@@ -155,6 +345,31 @@ impl Args {
             field_doc: false,
             field_attrs: None,
             from: false,
+            from_ref: false,
+            three_way: None,
+            fill: None,
+            merge_ref: None,
+            with: None,
+            merge_arc: None,
+            try_merge: None,
+            validate: None,
+            validate_error: None,
+            field_enum: false,
+            update_enum: None,
+            str_access: false,
+            from_env: false,
+            from_args: false,
+            query: false,
+            sql: None,
+            matches: false,
+            field_mask: false,
+            roles: None,
+            versions: false,
+            debug: false,
+            default: false,
+            views: false,
+            owned: false,
+            unused_generics: None,
         }
     }
 }
@@ -170,6 +385,30 @@ impl ArgList {
             field_doc: None,
             field_attrs: None,
             from: None,
+            three_way: None,
+            fill: None,
+            merge_ref: None,
+            with: None,
+            merge_arc: None,
+            try_merge: None,
+            validate: None,
+            validate_error: None,
+            field_enum: None,
+            update_enum: None,
+            str_access: None,
+            from_env: None,
+            from_args: None,
+            query: None,
+            sql: None,
+            matches: None,
+            field_mask: None,
+            roles: None,
+            versions: None,
+            debug: None,
+            default: None,
+            views: None,
+            owned: None,
+            unused_generics: None,
             list: Vec::with_capacity(6),
         }
     }
@@ -182,6 +421,30 @@ impl ArgList {
             || input.peek(kw::field_attrs)
             || input.peek(kw::attrs)
             || input.peek(kw::from)
+            || input.peek(kw::three_way)
+            || input.peek(kw::fill_fn)
+            || input.peek(kw::merge_ref_fn)
+            || input.peek(kw::with_fn)
+            || input.peek(kw::merge_arc_fn)
+            || input.peek(kw::try_merge_fn)
+            || input.peek(kw::validate)
+            || input.peek(kw::validate_error)
+            || input.peek(kw::field_enum)
+            || input.peek(kw::update_enum)
+            || input.peek(kw::str_access)
+            || input.peek(kw::from_env)
+            || input.peek(kw::from_args)
+            || input.peek(kw::query)
+            || input.peek(kw::sql)
+            || input.peek(kw::matches)
+            || input.peek(kw::field_mask)
+            || input.peek(kw::roles)
+            || input.peek(kw::versions)
+            || input.peek(kw::debug)
+            || input.peek(kw::default)
+            || input.peek(kw::views)
+            || input.peek(kw::owned)
+            || input.peek(kw::unused_generics)
     }
 
     fn parse_doc(&mut self, input: ParseStream) -> Result<()> {
@@ -280,8 +543,438 @@ impl ArgList {
         let span = input.span();
         input.parse::<kw::from>()?;
 
+        let (owned, by_ref) = if input.peek(Eq) {
+            input.parse::<Eq>()?;
+
+            let kinds: Vec<FromKind> = if input.peek(Paren) {
+                let content;
+                let paren = parenthesized!(content in input);
+
+                let kinds = Punctuated::<FromKind, Comma>::parse_terminated(&content)?;
+
+                if kinds.is_empty() {
+                    return Err(Error::new(paren.span.join(), "expected `owned` or `ref`"));
+                }
+
+                kinds.into_iter().collect()
+            } else {
+                vec![input.parse()?]
+            };
+
+            (
+                kinds.contains(&FromKind::Owned),
+                kinds.contains(&FromKind::Ref),
+            )
+        } else {
+            (true, false)
+        };
+
         self.from = Some(span);
-        self.list.push(Arg::From(true));
+        self.list.push(Arg::From(owned));
+        self.list.push(Arg::FromRef(by_ref));
+
+        Ok(())
+    }
+
+    fn parse_three_way(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(three_way_span) = self.three_way {
+            return ArgList::already_defined_error(input, "three_way", three_way_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::three_way>()?;
+        let three_way = MergeFn::parse_value(input)?;
+
+        self.three_way = Some(span);
+        self.list.push(Arg::ThreeWay(three_way));
+
+        Ok(())
+    }
+
+    fn parse_fill(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(fill_span) = self.fill {
+            return ArgList::already_defined_error(input, "fill_fn", fill_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::fill_fn>()?;
+        let fill = MergeFn::parse_value(input)?;
+
+        self.fill = Some(span);
+        self.list.push(Arg::Fill(fill));
+
+        Ok(())
+    }
+
+    fn parse_merge_ref(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(merge_ref_span) = self.merge_ref {
+            return ArgList::already_defined_error(input, "merge_ref_fn", merge_ref_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::merge_ref_fn>()?;
+        let merge_ref = MergeFn::parse_value(input)?;
+
+        self.merge_ref = Some(span);
+        self.list.push(Arg::MergeRef(merge_ref));
+
+        Ok(())
+    }
+
+    fn parse_with(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(with_span) = self.with {
+            return ArgList::already_defined_error(input, "with_fn", with_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::with_fn>()?;
+        let with = MergeFn::parse_value(input)?;
+
+        self.with = Some(span);
+        self.list.push(Arg::With(with));
+
+        Ok(())
+    }
+
+    fn parse_merge_arc(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(merge_arc_span) = self.merge_arc {
+            return ArgList::already_defined_error(input, "merge_arc_fn", merge_arc_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::merge_arc_fn>()?;
+        let merge_arc = MergeFn::parse_value(input)?;
+
+        self.merge_arc = Some(span);
+        self.list.push(Arg::MergeArc(merge_arc));
+
+        Ok(())
+    }
+
+    fn parse_try_merge(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(try_merge_span) = self.try_merge {
+            return ArgList::already_defined_error(input, "try_merge_fn", try_merge_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::try_merge_fn>()?;
+        let try_merge = MergeFn::parse_value(input)?;
+
+        self.try_merge = Some(span);
+        self.list.push(Arg::TryMerge(try_merge));
+
+        Ok(())
+    }
+
+    fn parse_validate(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(validate_span) = self.validate {
+            return ArgList::already_defined_error(input, "validate", validate_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::validate>()?;
+        input.parse::<Eq>()?;
+        let validate: Path = input.parse()?;
+
+        self.validate = Some(span);
+        self.list.push(Arg::Validate(validate));
+
+        Ok(())
+    }
+
+    fn parse_validate_error(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(validate_error_span) = self.validate_error {
+            return ArgList::already_defined_error(input, "validate_error", validate_error_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::validate_error>()?;
+        input.parse::<Eq>()?;
+        let validate_error: Type = input.parse()?;
+
+        self.validate_error = Some(span);
+        self.list.push(Arg::ValidateError(validate_error));
+
+        Ok(())
+    }
+
+    /// Checks arguments that are only valid alongside others.
+    fn check_dependencies(&self) -> Result<()> {
+        if let (Some(try_merge_span), None) = (self.try_merge, self.validate_error) {
+            return Err(Error::new(
+                try_merge_span,
+                "try_merge_fn requires validate_error",
+            ));
+        }
+
+        if self.try_merge.is_none() {
+            if let Some(validate_span) = self.validate.or(self.validate_error) {
+                return Err(Error::new(
+                    validate_span,
+                    "validate and validate_error require try_merge_fn",
+                ));
+            }
+        }
+
+        if let Some(owned_span) = self.owned {
+            // these tie opt fields to the original's borrowed field types
+            let conflicts = [
+                (self.merge, "merge_fn"),
+                (self.three_way, "three_way"),
+                (self.fill, "fill_fn"),
+                (self.merge_ref, "merge_ref_fn"),
+                (self.with, "with_fn"),
+                (self.merge_arc, "merge_arc_fn"),
+                (self.try_merge, "try_merge_fn"),
+                (self.update_enum, "update_enum"),
+                (self.matches, "matches"),
+                (self.field_mask, "field_mask"),
+                (self.views, "views"),
+            ];
+
+            if let Some((_, arg_name)) = conflicts.iter().find(|(span, _)| span.is_some()) {
+                return Err(Error::new(
+                    owned_span,
+                    format!("owned can't be combined with {}", arg_name),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_field_enum(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(field_enum_span) = self.field_enum {
+            return ArgList::already_defined_error(input, "field_enum", field_enum_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::field_enum>()?;
+
+        self.field_enum = Some(span);
+        self.list.push(Arg::FieldEnum(true));
+
+        Ok(())
+    }
+
+    fn parse_update_enum(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(update_enum_span) = self.update_enum {
+            return ArgList::already_defined_error(input, "update_enum", update_enum_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::update_enum>()?;
+        let update_enum = if input.peek(Eq) {
+            input.parse::<Eq>()?;
+
+            Attrs::parse_attr_list(input)?
+        } else {
+            Vec::new()
+        };
+
+        self.update_enum = Some(span);
+        self.list.push(Arg::UpdateEnum(update_enum));
+
+        Ok(())
+    }
+
+    fn parse_str_access(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(str_access_span) = self.str_access {
+            return ArgList::already_defined_error(input, "str_access", str_access_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::str_access>()?;
+
+        self.str_access = Some(span);
+        self.list.push(Arg::StrAccess(true));
+
+        Ok(())
+    }
+
+    fn parse_from_env(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(from_env_span) = self.from_env {
+            return ArgList::already_defined_error(input, "from_env", from_env_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::from_env>()?;
+
+        self.from_env = Some(span);
+        self.list.push(Arg::FromEnv(true));
+
+        Ok(())
+    }
+
+    fn parse_from_args(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(from_args_span) = self.from_args {
+            return ArgList::already_defined_error(input, "from_args", from_args_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::from_args>()?;
+
+        self.from_args = Some(span);
+        self.list.push(Arg::FromArgs(true));
+
+        Ok(())
+    }
+
+    fn parse_query(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(query_span) = self.query {
+            return ArgList::already_defined_error(input, "query", query_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::query>()?;
+
+        self.query = Some(span);
+        self.list.push(Arg::Query(true));
+
+        Ok(())
+    }
+
+    fn parse_sql(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(sql_span) = self.sql {
+            return ArgList::already_defined_error(input, "sql", sql_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::sql>()?;
+        let sql: Placeholder = input.parse()?;
+
+        self.sql = Some(span);
+        self.list.push(Arg::Sql(sql));
+
+        Ok(())
+    }
+
+    fn parse_matches(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(matches_span) = self.matches {
+            return ArgList::already_defined_error(input, "matches", matches_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::matches>()?;
+
+        self.matches = Some(span);
+        self.list.push(Arg::Matches(true));
+
+        Ok(())
+    }
+
+    fn parse_field_mask(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(field_mask_span) = self.field_mask {
+            return ArgList::already_defined_error(input, "field_mask", field_mask_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::field_mask>()?;
+
+        self.field_mask = Some(span);
+        self.list.push(Arg::FieldMask(true));
+
+        Ok(())
+    }
+
+    fn parse_roles(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(roles_span) = self.roles {
+            return ArgList::already_defined_error(input, "roles", roles_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::roles>()?;
+        input.parse::<Eq>()?;
+        let roles: Type = input.parse()?;
+
+        self.roles = Some(span);
+        self.list.push(Arg::Roles(roles));
+
+        Ok(())
+    }
+
+    fn parse_versions(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(versions_span) = self.versions {
+            return ArgList::already_defined_error(input, "versions", versions_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::versions>()?;
+
+        self.versions = Some(span);
+        self.list.push(Arg::Versions(true));
+
+        Ok(())
+    }
+
+    fn parse_debug(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(debug_span) = self.debug {
+            return ArgList::already_defined_error(input, "debug", debug_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::debug>()?;
+
+        self.debug = Some(span);
+        self.list.push(Arg::Debug(true));
+
+        Ok(())
+    }
+
+    fn parse_default(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(default_span) = self.default {
+            return ArgList::already_defined_error(input, "default", default_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::default>()?;
+
+        self.default = Some(span);
+        self.list.push(Arg::Default(true));
+
+        Ok(())
+    }
+
+    fn parse_views(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(views_span) = self.views {
+            return ArgList::already_defined_error(input, "views", views_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::views>()?;
+
+        self.views = Some(span);
+        self.list.push(Arg::Views(true));
+
+        Ok(())
+    }
+
+    fn parse_owned(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(owned_span) = self.owned {
+            return ArgList::already_defined_error(input, "owned", owned_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::owned>()?;
+
+        self.owned = Some(span);
+        self.list.push(Arg::Owned(true));
+
+        Ok(())
+    }
+
+    fn parse_unused_generics(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(unused_generics_span) = self.unused_generics {
+            return ArgList::already_defined_error(input, "unused_generics", unused_generics_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::unused_generics>()?;
+        input.parse::<Eq>()?;
+        let unused_generics: UnusedGenerics = input.parse()?;
+
+        self.unused_generics = Some(span);
+        self.list.push(Arg::UnusedGenerics(unused_generics));
 
         Ok(())
     }
@@ -340,6 +1033,13 @@ impl Parse for MergeFn {
     fn parse(input: ParseStream) -> Result<Self> {
         input.parse::<kw::merge_fn>()?;
 
+        MergeFn::parse_value(input)
+    }
+}
+
+impl MergeFn {
+    /// Parses the optional `= [visibility] [name]` part of method arguments.
+    fn parse_value(input: ParseStream) -> Result<Self> {
         if input.peek(Eq) {
             input.parse::<Eq>()?;
 
@@ -360,6 +1060,13 @@ impl Parse for MergeFn {
             Ok(MergeFn::default())
         }
     }
+
+    pub fn ident(&self, default_name: &str) -> Ident {
+        match &self.name {
+            MergeFnName::Custom(n) => n.clone(),
+            MergeFnName::Default => Ident::new(default_name, Span::call_site()),
+        }
+    }
 }
 
 impl Default for MergeFn {
@@ -417,21 +1124,106 @@ impl Parse for AttrList {
     }
 }
 
-impl From<ArgList> for Args {
-    fn from(arg_list: ArgList) -> Args {
-        use Arg::*;
+impl Parse for FromKind {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
 
-        let mut args = Args::new(arg_list.item);
+        if lookahead.peek(kw::from_sub::owned) {
+            input.parse::<kw::from_sub::owned>()?;
 
-        for arg in arg_list.list {
-            match arg {
-                Merge(merge) => args.merge = Some(merge),
-                Doc(doc) => args.doc = Some(doc),
-                Rewrap(rewrap) => args.rewrap = rewrap,
-                Attrs(attrs) => args.attrs = Some(attrs),
-                FieldDocs(field_doc) => args.field_doc = field_doc,
-                FieldAttrs(field_attrs) => args.field_attrs = Some(field_attrs),
-                From(from) => args.from = from,
+            Ok(FromKind::Owned)
+        } else if lookahead.peek(Ref) {
+            input.parse::<Ref>()?;
+
+            Ok(FromKind::Ref)
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl Parse for UnusedGenerics {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+
+        if lookahead.peek(kw::unused_generics_sub::prune) {
+            input.parse::<kw::unused_generics_sub::prune>()?;
+
+            Ok(UnusedGenerics::Prune)
+        } else if lookahead.peek(kw::unused_generics_sub::phantom) {
+            input.parse::<kw::unused_generics_sub::phantom>()?;
+
+            Ok(UnusedGenerics::Phantom)
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl Parse for Placeholder {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Eq) {
+            input.parse::<Eq>()?;
+
+            let lookahead = input.lookahead1();
+
+            if lookahead.peek(kw::sql_sub::question) {
+                input.parse::<kw::sql_sub::question>()?;
+
+                Ok(Placeholder::Question)
+            } else if lookahead.peek(kw::sql_sub::numbered) {
+                input.parse::<kw::sql_sub::numbered>()?;
+
+                Ok(Placeholder::Numbered)
+            } else {
+                Err(lookahead.error())
+            }
+        } else {
+            Ok(Placeholder::Question)
+        }
+    }
+}
+
+impl From<ArgList> for Args {
+    fn from(arg_list: ArgList) -> Args {
+        use Arg::*;
+
+        let mut args = Args::new(arg_list.item);
+
+        for arg in arg_list.list {
+            match arg {
+                Merge(merge) => args.merge = Some(merge),
+                Doc(doc) => args.doc = Some(doc),
+                Rewrap(rewrap) => args.rewrap = rewrap,
+                Attrs(attrs) => args.attrs = Some(attrs),
+                FieldDocs(field_doc) => args.field_doc = field_doc,
+                FieldAttrs(field_attrs) => args.field_attrs = Some(field_attrs),
+                From(from) => args.from = from,
+                FromRef(from_ref) => args.from_ref = from_ref,
+                ThreeWay(three_way) => args.three_way = Some(three_way),
+                Fill(fill) => args.fill = Some(fill),
+                MergeRef(merge_ref) => args.merge_ref = Some(merge_ref),
+                With(with) => args.with = Some(with),
+                MergeArc(merge_arc) => args.merge_arc = Some(merge_arc),
+                TryMerge(try_merge) => args.try_merge = Some(try_merge),
+                Validate(validate) => args.validate = Some(validate),
+                ValidateError(validate_error) => args.validate_error = Some(validate_error),
+                FieldEnum(field_enum) => args.field_enum = field_enum,
+                UpdateEnum(update_enum) => args.update_enum = Some(update_enum),
+                StrAccess(str_access) => args.str_access = str_access,
+                FromEnv(from_env) => args.from_env = from_env,
+                FromArgs(from_args) => args.from_args = from_args,
+                Query(query) => args.query = query,
+                Sql(sql) => args.sql = Some(sql),
+                Matches(matches) => args.matches = matches,
+                FieldMask(field_mask) => args.field_mask = field_mask,
+                Roles(roles) => args.roles = Some(roles),
+                Versions(versions) => args.versions = versions,
+                Debug(debug) => args.debug = debug,
+                Default(default) => args.default = default,
+                Views(views) => args.views = views,
+                Owned(owned) => args.owned = owned,
+                UnusedGenerics(unused_generics) => args.unused_generics = Some(unused_generics),
             }
         }
 
@@ -476,6 +1268,26 @@ mod tests {
     duplicate_arg_panics_test!(field_doc, "field_doc already defined");
     duplicate_arg_panics_test!(field_attrs, "field_attrs already defined");
     duplicate_arg_panics_test!(from, "from already defined");
+    duplicate_arg_panics_test!(three_way, "three_way already defined");
+    duplicate_arg_panics_test!(fill_fn, "fill_fn already defined");
+    duplicate_arg_panics_test!(merge_ref_fn, "merge_ref_fn already defined");
+    duplicate_arg_panics_test!(with_fn, "with_fn already defined");
+    duplicate_arg_panics_test!(merge_arc_fn, "merge_arc_fn already defined");
+    duplicate_arg_panics_test!(try_merge_fn, "try_merge_fn already defined");
+    duplicate_arg_panics_test!(field_enum, "field_enum already defined");
+    duplicate_arg_panics_test!(update_enum, "update_enum already defined");
+    duplicate_arg_panics_test!(str_access, "str_access already defined");
+    duplicate_arg_panics_test!(from_env, "from_env already defined");
+    duplicate_arg_panics_test!(from_args, "from_args already defined");
+    duplicate_arg_panics_test!(query, "query already defined");
+    duplicate_arg_panics_test!(sql, "sql already defined");
+    duplicate_arg_panics_test!(matches, "matches already defined");
+    duplicate_arg_panics_test!(field_mask, "field_mask already defined");
+    duplicate_arg_panics_test!(versions, "versions already defined");
+    duplicate_arg_panics_test!(debug, "debug already defined");
+    duplicate_arg_panics_test!(default, "default already defined");
+    duplicate_arg_panics_test!(views, "views already defined");
+    duplicate_arg_panics_test!(owned, "owned already defined");
 
     macro_rules! struct_name_not_first_panics {
         ($attr:meta) => {
@@ -499,6 +1311,30 @@ mod tests {
     struct_name_not_first_panics!(field_doc);
     struct_name_not_first_panics!(field_attrs);
     struct_name_not_first_panics!(from);
+    struct_name_not_first_panics!(three_way);
+    struct_name_not_first_panics!(fill_fn);
+    struct_name_not_first_panics!(merge_ref_fn);
+    struct_name_not_first_panics!(with_fn);
+    struct_name_not_first_panics!(merge_arc_fn);
+    struct_name_not_first_panics!(try_merge_fn);
+    struct_name_not_first_panics!(validate);
+    struct_name_not_first_panics!(validate_error);
+    struct_name_not_first_panics!(field_enum);
+    struct_name_not_first_panics!(update_enum);
+    struct_name_not_first_panics!(str_access);
+    struct_name_not_first_panics!(from_env);
+    struct_name_not_first_panics!(from_args);
+    struct_name_not_first_panics!(query);
+    struct_name_not_first_panics!(sql);
+    struct_name_not_first_panics!(matches);
+    struct_name_not_first_panics!(field_mask);
+    struct_name_not_first_panics!(roles);
+    struct_name_not_first_panics!(versions);
+    struct_name_not_first_panics!(debug);
+    struct_name_not_first_panics!(default);
+    struct_name_not_first_panics!(views);
+    struct_name_not_first_panics!(owned);
+    struct_name_not_first_panics!(unused_generics);
 
     #[test]
     #[should_panic(expected = "expected opt struct name")]
@@ -539,12 +1375,37 @@ mod tests {
         assert_eq!(args.item.visibility, None);
         assert_eq!(args.item.final_visibility(), Visibility::Inherited);
         assert_eq!(args.merge, None);
-        assert_eq!(args.rewrap, false);
+        assert!(!args.rewrap);
         assert_eq!(args.doc, None);
         assert_eq!(args.attrs, None);
-        assert_eq!(args.field_doc, false);
+        assert!(!args.field_doc);
         assert_eq!(args.field_attrs, None);
-        assert_eq!(args.from, false);
+        assert!(!args.from);
+        assert!(!args.from_ref);
+        assert_eq!(args.three_way, None);
+        assert_eq!(args.fill, None);
+        assert_eq!(args.merge_ref, None);
+        assert_eq!(args.with, None);
+        assert_eq!(args.merge_arc, None);
+        assert_eq!(args.try_merge, None);
+        assert_eq!(args.validate, None);
+        assert_eq!(args.validate_error, None);
+        assert!(!args.field_enum);
+        assert_eq!(args.update_enum, None);
+        assert!(!args.str_access);
+        assert!(!args.from_env);
+        assert!(!args.from_args);
+        assert!(!args.query);
+        assert_eq!(args.sql, None);
+        assert!(!args.matches);
+        assert!(!args.field_mask);
+        assert_eq!(args.roles, None);
+        assert!(!args.versions);
+        assert!(!args.debug);
+        assert!(!args.default);
+        assert!(!args.views);
+        assert!(!args.owned);
+        assert_eq!(args.unused_generics, None);
     }
 
     #[test]
@@ -728,11 +1589,482 @@ mod tests {
 
     #[test]
     fn parse_from() {
+        let cases = vec![
+            (quote! {Opt, from}, (true, false)),
+            (quote! {Opt, from = owned}, (true, false)),
+            (quote! {Opt, from = ref}, (false, true)),
+            (quote! {Opt, from = (owned, ref)}, (true, true)),
+            (quote! {Opt, from = (ref)}, (false, true)),
+        ];
+
+        for (args_tokens, (owned, by_ref)) in cases {
+            let args = parse_args(args_tokens);
+
+            assert_eq!(args.from, owned);
+            assert_eq!(args.from_ref, by_ref);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "expected `owned` or `ref`")]
+    fn empty_from_kinds_panics() {
+        parse_args(quote! {
+            Opt,
+            from = ()
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "expected `owned` or `ref`")]
+    fn unknown_from_kind_panics() {
+        parse_args(quote! {
+            Opt,
+            from = borrowed
+        });
+    }
+
+    #[test]
+    fn parse_three_way() {
+        let custom_fn_name = MergeFnName::Custom(syn::parse2(quote!(custom_fn)).unwrap());
+
+        let cases = vec![
+            (
+                quote! {Opt, three_way},
+                MergeFnName::Default,
+                Visibility::Inherited,
+            ),
+            (
+                quote! {Opt, three_way = pub(crate) custom_fn},
+                custom_fn_name,
+                syn::parse2(quote!(pub(crate))).unwrap(),
+            ),
+        ];
+
+        for (args_tokens, fn_name, vis) in cases {
+            let args = parse_args(args_tokens);
+
+            assert_eq!(args.three_way.clone().unwrap().name, fn_name);
+            assert_eq!(args.three_way.unwrap().visibility, vis);
+        }
+    }
+
+    #[test]
+    fn parse_fill_fn() {
+        let custom_fn_name = MergeFnName::Custom(syn::parse2(quote!(custom_fn)).unwrap());
+
+        let cases = vec![
+            (
+                quote! {Opt, fill_fn},
+                MergeFnName::Default,
+                Visibility::Inherited,
+            ),
+            (
+                quote! {Opt, fill_fn = pub custom_fn},
+                custom_fn_name,
+                syn::parse2(quote!(pub)).unwrap(),
+            ),
+        ];
+
+        for (args_tokens, fn_name, vis) in cases {
+            let args = parse_args(args_tokens);
+
+            assert_eq!(args.fill.clone().unwrap().name, fn_name);
+            assert_eq!(args.fill.unwrap().visibility, vis);
+        }
+    }
+
+    #[test]
+    fn parse_merge_ref_fn() {
+        let custom_fn_name = MergeFnName::Custom(syn::parse2(quote!(custom_fn)).unwrap());
+
+        let cases = vec![
+            (
+                quote! {Opt, merge_ref_fn},
+                MergeFnName::Default,
+                Visibility::Inherited,
+            ),
+            (
+                quote! {Opt, merge_ref_fn = pub(crate) custom_fn},
+                custom_fn_name,
+                syn::parse2(quote!(pub(crate))).unwrap(),
+            ),
+        ];
+
+        for (args_tokens, fn_name, vis) in cases {
+            let args = parse_args(args_tokens);
+
+            assert_eq!(args.merge_ref.clone().unwrap().name, fn_name);
+            assert_eq!(args.merge_ref.unwrap().visibility, vis);
+        }
+    }
+
+    #[test]
+    fn parse_with_fn() {
+        let custom_fn_name = MergeFnName::Custom(syn::parse2(quote!(custom_fn)).unwrap());
+
+        let cases = vec![
+            (
+                quote! {Opt, with_fn},
+                MergeFnName::Default,
+                Visibility::Inherited,
+            ),
+            (
+                quote! {Opt, with_fn = pub custom_fn},
+                custom_fn_name,
+                syn::parse2(quote!(pub)).unwrap(),
+            ),
+        ];
+
+        for (args_tokens, fn_name, vis) in cases {
+            let args = parse_args(args_tokens);
+
+            assert_eq!(args.with.clone().unwrap().name, fn_name);
+            assert_eq!(args.with.unwrap().visibility, vis);
+        }
+    }
+
+    #[test]
+    fn parse_merge_arc_fn() {
+        let custom_fn_name = MergeFnName::Custom(syn::parse2(quote!(custom_fn)).unwrap());
+
+        let cases = vec![
+            (
+                quote! {Opt, merge_arc_fn},
+                MergeFnName::Default,
+                Visibility::Inherited,
+            ),
+            (
+                quote! {Opt, merge_arc_fn = pub custom_fn},
+                custom_fn_name,
+                syn::parse2(quote!(pub)).unwrap(),
+            ),
+        ];
+
+        for (args_tokens, fn_name, vis) in cases {
+            let args = parse_args(args_tokens);
+
+            assert_eq!(args.merge_arc.clone().unwrap().name, fn_name);
+            assert_eq!(args.merge_arc.unwrap().visibility, vis);
+        }
+    }
+
+    #[test]
+    fn parse_try_merge_fn() {
+        let custom_fn_name = MergeFnName::Custom(syn::parse2(quote!(custom_fn)).unwrap());
+
+        let cases = vec![
+            (
+                quote! {Opt, try_merge_fn, validate_error = Error},
+                MergeFnName::Default,
+                Visibility::Inherited,
+            ),
+            (
+                quote! {Opt, try_merge_fn = pub(crate) custom_fn, validate_error = Error},
+                custom_fn_name,
+                syn::parse2(quote!(pub(crate))).unwrap(),
+            ),
+        ];
+
+        for (args_tokens, fn_name, vis) in cases {
+            let args = parse_args(args_tokens);
+
+            assert_eq!(args.try_merge.clone().unwrap().name, fn_name);
+            assert_eq!(args.try_merge.unwrap().visibility, vis);
+        }
+    }
+
+    #[test]
+    fn parse_validate() {
+        let args = parse_args(quote! {
+            Opt,
+            try_merge_fn,
+            validate = path::to::check,
+            validate_error = Error
+        });
+
+        assert_eq!(
+            args.validate,
+            Some(syn::parse2(quote!(path::to::check)).unwrap())
+        );
+        assert_eq!(args.validate_error, Some(parse_type(quote!(Error))));
+    }
+
+    #[test]
+    #[should_panic(expected = "try_merge_fn requires validate_error")]
+    fn try_merge_fn_without_validate_error_panics() {
+        parse_args(quote! {
+            Opt,
+            try_merge_fn,
+            validate = check
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "validate and validate_error require try_merge_fn")]
+    fn validate_without_try_merge_fn_panics() {
+        parse_args(quote! {
+            Opt,
+            validate = check,
+            validate_error = Error
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "validate already defined")]
+    fn duplicate_validate_panics() {
+        parse_args(quote! {
+            Opt,
+            validate = check,
+            validate = other_check
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "validate_error already defined")]
+    fn duplicate_validate_error_panics() {
+        parse_args(quote! {
+            Opt,
+            validate_error = Error,
+            validate_error = OtherError
+        });
+    }
+
+    #[test]
+    fn parse_field_enum() {
+        let args = parse_args(quote! {
+            Opt,
+            field_enum
+        });
+
+        assert!(args.field_enum);
+    }
+
+    #[test]
+    fn parse_update_enum() {
+        let cases = vec![
+            (quote! {Opt, update_enum}, Vec::new()),
+            (
+                quote! {Opt, update_enum = (derive(Clone, Debug))},
+                Attrs::parse_attr_list
+                    .parse2(quote! {(derive(Clone, Debug))})
+                    .unwrap(),
+            ),
+        ];
+
+        for (args_tokens, attrs) in cases {
+            let args = parse_args(args_tokens);
+
+            assert_eq!(args.update_enum, Some(attrs));
+        }
+    }
+
+    #[test]
+    fn parse_str_access() {
+        let args = parse_args(quote! {
+            Opt,
+            str_access
+        });
+
+        assert!(args.str_access);
+    }
+
+    #[test]
+    fn parse_from_env() {
+        let args = parse_args(quote! {
+            Opt,
+            from_env
+        });
+
+        assert!(args.from_env);
+    }
+
+    #[test]
+    fn parse_from_args() {
+        let args = parse_args(quote! {
+            Opt,
+            from_args
+        });
+
+        assert!(args.from_args);
+    }
+
+    #[test]
+    fn parse_query() {
+        let args = parse_args(quote! {
+            Opt,
+            query
+        });
+
+        assert!(args.query);
+    }
+
+    #[test]
+    fn parse_sql() {
+        let cases = vec![
+            (quote! {Opt, sql}, Placeholder::Question),
+            (quote! {Opt, sql = question}, Placeholder::Question),
+            (quote! {Opt, sql = numbered}, Placeholder::Numbered),
+        ];
+
+        for (args_tokens, placeholder) in cases {
+            let args = parse_args(args_tokens);
+
+            assert_eq!(args.sql, Some(placeholder));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "expected `question` or `numbered`")]
+    fn unknown_sql_placeholder_panics() {
+        parse_args(quote! {
+            Opt,
+            sql = named
+        });
+    }
+
+    #[test]
+    fn parse_matches() {
+        let args = parse_args(quote! {
+            Opt,
+            matches
+        });
+
+        assert!(args.matches);
+    }
+
+    #[test]
+    fn parse_field_mask() {
         let args = parse_args(quote! {
             Opt,
-            from
+            field_mask
         });
 
-        assert!(args.from);
+        assert!(args.field_mask);
+    }
+
+    #[test]
+    fn parse_roles() {
+        let args = parse_args(quote! {
+            Opt,
+            roles = path::to::Role
+        });
+
+        assert_eq!(args.roles, Some(parse_type(quote!(path::to::Role))));
+    }
+
+    #[test]
+    #[should_panic(expected = "roles already defined")]
+    fn duplicate_roles_panics() {
+        parse_args(quote! {
+            Opt,
+            roles = Role,
+            roles = OtherRole
+        });
+    }
+
+    #[test]
+    fn parse_versions() {
+        let args = parse_args(quote! {
+            Opt,
+            versions
+        });
+
+        assert!(args.versions);
+    }
+
+    #[test]
+    fn parse_debug() {
+        let args = parse_args(quote! {
+            Opt,
+            debug
+        });
+
+        assert!(args.debug);
+    }
+
+    #[test]
+    fn parse_default() {
+        let args = parse_args(quote! {
+            Opt,
+            default
+        });
+
+        assert!(args.default);
+    }
+
+    #[test]
+    fn parse_views() {
+        let args = parse_args(quote! {
+            Opt,
+            views
+        });
+
+        assert!(args.views);
+    }
+
+    #[test]
+    fn parse_owned() {
+        let args = parse_args(quote! {
+            Opt,
+            owned
+        });
+
+        assert!(args.owned);
+    }
+
+    #[test]
+    #[should_panic(expected = "owned can't be combined with merge_fn")]
+    fn owned_with_merge_fn_panics() {
+        parse_args(quote! {
+            Opt,
+            owned,
+            merge_fn
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "owned can't be combined with views")]
+    fn owned_with_views_panics() {
+        parse_args(quote! {
+            Opt,
+            views,
+            owned
+        });
+    }
+
+    #[test]
+    fn parse_unused_generics() {
+        let cases = vec![
+            (quote! {Opt, unused_generics = prune}, UnusedGenerics::Prune),
+            (
+                quote! {Opt, unused_generics = phantom},
+                UnusedGenerics::Phantom,
+            ),
+        ];
+
+        for (args_tokens, unused_generics) in cases {
+            let args = parse_args(args_tokens);
+
+            assert_eq!(args.unused_generics, Some(unused_generics));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "expected `prune` or `phantom`")]
+    fn unknown_unused_generics_panics() {
+        parse_args(quote! {
+            Opt,
+            unused_generics = keep
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "unused_generics already defined")]
+    fn duplicate_unused_generics_panics() {
+        parse_args(quote! {
+            Opt,
+            unused_generics = prune,
+            unused_generics = phantom
+        });
     }
 }
@@ -60,7 +60,7 @@ pub trait AttrGenerator {
                 }
             }
 
-            if attr.path().is_ident(OPT_ATTR) {
+            if is_optfield_attr(attr) {
                 add_attr = false
             }
 
@@ -86,3 +86,7 @@ pub trait AttrGenerator {
 pub fn is_doc_attr(attr: &Attribute) -> bool {
     attr.path().is_ident(DOC)
 }
+
+pub fn is_optfield_attr(attr: &Attribute) -> bool {
+    attr.path().is_ident(OPT_ATTR)
+}
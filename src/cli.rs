@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Error, ItemStruct, Type};
+
+use crate::args::Args;
+use crate::fields;
+
+pub fn generate(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if args.from_args {
+        let vis = &opt_item.vis;
+
+        let opt_name = &opt_item.ident;
+        let (impl_generics, opt_ty_generics, where_clause) = opt_item.generics.split_for_impl();
+
+        let error_name = format_ident!("{}ArgsError", opt_name);
+
+        let mut parsed_types = Vec::new();
+        let mut empty_fields = TokenStream::new();
+        let mut arg_matches = TokenStream::new();
+        let mut used_flags: HashMap<String, String> = HashMap::new();
+
+        for (i, field) in item.fields.iter().enumerate() {
+            let cfg_attrs = fields::cfg_attrs(field);
+            let field_name = fields::member(i, field);
+
+            if fields::is_marker(field) {
+                let value = fields::marker_value(field);
+
+                empty_fields.extend(quote! {
+                    #cfg_attrs
+                    #field_name: #value,
+                });
+
+                continue;
+            }
+
+            let (parsed_type, value) = fields::parsed_type(field, args);
+
+            let long = match fields::args::get(field).long {
+                Some(long) => long.value(),
+                None => fields::name(i, field).replace('_', "-"),
+            };
+            let flag = format!("--{}", long);
+            let negated_flag = format!("--no-{}", long);
+
+            let mut flags = vec![flag.clone()];
+
+            if is_bool(&parsed_type) {
+                flags.push(negated_flag.clone());
+            }
+
+            for field_flag in flags {
+                let field_name_str = fields::name(i, field);
+
+                if let Some(other) = used_flags.get(&field_flag) {
+                    return Error::new_spanned(
+                        field,
+                        format!(
+                            "flag `{}` of field `{}` is already used by field `{}`",
+                            field_flag, field_name_str, other
+                        ),
+                    )
+                    .to_compile_error();
+                }
+
+                used_flags.insert(field_flag, field_name_str);
+            }
+
+            empty_fields.extend(quote! {
+                #cfg_attrs
+                #field_name: None,
+            });
+
+            let field_matches = if is_bool(&parsed_type) {
+                quote! {
+                    #flag => {
+                        let value = match inline_value {
+                            Some(value) => value.parse::<bool>().map_err(|e| #error_name::InvalidValue {
+                                arg: #flag.to_string(),
+                                message: e.to_string(),
+                            })?,
+                            None => true,
+                        };
+
+                        opt.#field_name = Some(#value);
+                    }
+                    #cfg_attrs
+                    #negated_flag => {
+                        if inline_value.is_some() {
+                            return Err(#error_name::ValueOnNegatedFlag(#negated_flag.to_string()));
+                        }
+
+                        let value = false;
+
+                        opt.#field_name = Some(#value);
+                    }
+                }
+            } else {
+                let field_matches = quote! {
+                    #flag => {
+                        let value = match inline_value {
+                            Some(value) => value.to_string(),
+                            None => args
+                                .next()
+                                .ok_or_else(|| #error_name::MissingValue(#flag.to_string()))?,
+                        };
+
+                        let value = value.parse::<#parsed_type>().map_err(|e| #error_name::InvalidValue {
+                            arg: #flag.to_string(),
+                            message: e.to_string(),
+                        })?;
+
+                        opt.#field_name = Some(#value);
+                    }
+                };
+
+                parsed_types.push(parsed_type);
+
+                field_matches
+            };
+
+            arg_matches.extend(quote! {
+                #cfg_attrs
+                #field_matches
+            });
+        }
+
+        quote! {
+            #[derive(Clone, Debug, PartialEq, Eq)]
+            #vis enum #error_name {
+                MissingValue(String),
+                InvalidValue {
+                    arg: String,
+                    message: String,
+                },
+                ValueOnNegatedFlag(String),
+            }
+
+            impl ::std::fmt::Display for #error_name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    match self {
+                        #error_name::MissingValue(arg) => write!(f, "missing value for `{}`", arg),
+                        #error_name::InvalidValue { arg, message } => {
+                            write!(f, "invalid value for `{}`: {}", arg, message)
+                        }
+                        #error_name::ValueOnNegatedFlag(arg) => {
+                            write!(f, "`{}` doesn't take a value", arg)
+                        }
+                    }
+                }
+            }
+
+            impl ::std::error::Error for #error_name {}
+
+            impl #impl_generics #opt_name #opt_ty_generics #where_clause {
+                #vis fn from_args<I>(args: I) -> Result<(Self, Vec<String>), #error_name>
+                where
+                    I: IntoIterator<Item = String>,
+                    #(
+                        #parsed_types: ::std::str::FromStr,
+                        <#parsed_types as ::std::str::FromStr>::Err: ::std::fmt::Display,
+                    )*
+                {
+                    let mut opt = #opt_name { #empty_fields };
+                    let mut rest = Vec::new();
+
+                    let mut args = args.into_iter();
+
+                    while let Some(arg) = args.next() {
+                        // everything after `--` is left to the caller
+                        if arg == "--" {
+                            rest.push(arg);
+                            rest.extend(args);
+
+                            break;
+                        }
+
+                        let (name, inline_value) = match arg.split_once('=') {
+                            Some((name, value)) if name.starts_with("--") => (name, Some(value)),
+                            _ => (arg.as_str(), None),
+                        };
+
+                        match name {
+                            #arg_matches
+                            _ => rest.push(arg.clone()),
+                        }
+                    }
+
+                    Ok((opt, rest))
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
+
+fn is_bool(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) => type_path.path.is_ident("bool"),
+        _ => false,
+    }
+}
@@ -0,0 +1,66 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, ItemStruct};
+
+use crate::args::Args;
+use crate::fields;
+
+pub fn generate(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if args.debug {
+        let opt_name = &opt_item.ident;
+        let opt_name_str = opt_name.to_string();
+
+        let mut generics = opt_item.generics.clone();
+        let where_clause = generics.make_where_clause();
+
+        let mut debug_fields = TokenStream::new();
+
+        for (i, field) in item.fields.iter().enumerate() {
+            if fields::is_marker(field) {
+                continue;
+            }
+
+            let cfg_attrs = fields::cfg_attrs(field);
+            let field_name = fields::member(i, field);
+            let field_name_str = fields::name(i, field);
+
+            // sensitive values are never formatted, so they need no bound
+            let value = if fields::args::get(field).sensitive {
+                quote!(&format_args!("***"))
+            } else {
+                let value_type = fields::value_type(field, args);
+
+                where_clause
+                    .predicates
+                    .push(parse_quote!(#value_type: ::std::fmt::Debug));
+
+                quote!(value)
+            };
+
+            debug_fields.extend(quote! {
+                #cfg_attrs
+                {
+                    if let Some(value) = &self.#field_name {
+                        debug.field(#field_name_str, #value);
+                    }
+                }
+            });
+        }
+
+        let (impl_generics, opt_ty_generics, where_clause) = generics.split_for_impl();
+
+        quote! {
+            impl #impl_generics ::std::fmt::Debug for #opt_name #opt_ty_generics #where_clause {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    let mut debug = f.debug_struct(#opt_name_str);
+
+                    #debug_fields
+
+                    debug.finish()
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
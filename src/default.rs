@@ -0,0 +1,51 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ItemStruct;
+
+use crate::args::Args;
+use crate::fields;
+
+pub fn generate(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if args.default {
+        let vis = &opt_item.vis;
+
+        let opt_name = &opt_item.ident;
+        let (impl_generics, opt_ty_generics, where_clause) = opt_item.generics.split_for_impl();
+
+        let mut empty_fields = TokenStream::new();
+
+        for (i, field) in item.fields.iter().enumerate() {
+            let cfg_attrs = fields::cfg_attrs(field);
+            let field_name = fields::member(i, field);
+
+            let value = if fields::is_marker(field) {
+                fields::marker_value(field)
+            } else {
+                quote!(None)
+            };
+
+            empty_fields.extend(quote! {
+                #cfg_attrs
+                #field_name: #value,
+            });
+        }
+
+        quote! {
+            impl #impl_generics #opt_name #opt_ty_generics #where_clause {
+                #vis const EMPTY: Self = Self::none();
+
+                #vis const fn none() -> Self {
+                    #opt_name { #empty_fields }
+                }
+            }
+
+            impl #impl_generics ::std::default::Default for #opt_name #opt_ty_generics #where_clause {
+                fn default() -> Self {
+                    Self::none()
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
@@ -0,0 +1,164 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ItemStruct;
+
+use crate::args::Args;
+use crate::fields;
+
+pub fn generate(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if args.from_env {
+        let vis = &opt_item.vis;
+
+        let opt_name = &opt_item.ident;
+        let (impl_generics, opt_ty_generics, where_clause) = opt_item.generics.split_for_impl();
+
+        let error_name = format_ident!("{}EnvError", opt_name);
+
+        let mut parsed_types = Vec::new();
+        let mut empty_fields = TokenStream::new();
+        let mut var_lookups = TokenStream::new();
+        let mut var_matches = TokenStream::new();
+
+        for (i, field) in item.fields.iter().enumerate() {
+            let cfg_attrs = fields::cfg_attrs(field);
+            let field_name = fields::member(i, field);
+
+            if fields::is_marker(field) {
+                let value = fields::marker_value(field);
+
+                empty_fields.extend(quote! {
+                    #cfg_attrs
+                    #field_name: #value,
+                });
+
+                continue;
+            }
+
+            let (parsed_type, value) = fields::parsed_type(field, args);
+
+            // an env field arg replaces the whole variable name, prefix included
+            let var = match fields::args::get(field).env {
+                Some(env) => quote!(#env.to_string()),
+                None => {
+                    let suffix = fields::name(i, field).to_uppercase();
+
+                    quote! {
+                        if prefix.is_empty() {
+                            #suffix.to_string()
+                        } else {
+                            format!("{}_{}", prefix, #suffix)
+                        }
+                    }
+                }
+            };
+
+            let parse = quote! {
+                let value = value.parse::<#parsed_type>().map_err(|e| #error_name::InvalidValue {
+                    var,
+                    message: e.to_string(),
+                })?;
+
+                opt.#field_name = Some(#value);
+            };
+
+            empty_fields.extend(quote! {
+                #cfg_attrs
+                #field_name: None,
+            });
+
+            var_lookups.extend(quote! {
+                #cfg_attrs
+                {
+                    let var = #var;
+
+                    match ::std::env::var(&var) {
+                        Ok(value) => {
+                            #parse
+                        }
+                        Err(::std::env::VarError::NotPresent) => {}
+                        Err(::std::env::VarError::NotUnicode(_)) => {
+                            return Err(#error_name::NotUnicode(var));
+                        }
+                    }
+                }
+            });
+
+            var_matches.extend(quote! {
+                #cfg_attrs
+                {
+                    let var = #var;
+
+                    if key == var {
+                        #parse
+
+                        continue;
+                    }
+                }
+            });
+
+            parsed_types.push(parsed_type);
+        }
+
+        let bounds = quote! {
+            #(
+                #parsed_types: ::std::str::FromStr,
+                <#parsed_types as ::std::str::FromStr>::Err: ::std::fmt::Display,
+            )*
+        };
+
+        quote! {
+            #[derive(Clone, Debug, PartialEq, Eq)]
+            #vis enum #error_name {
+                InvalidValue {
+                    var: String,
+                    message: String,
+                },
+                NotUnicode(String),
+            }
+
+            impl ::std::fmt::Display for #error_name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    match self {
+                        #error_name::InvalidValue { var, message } => {
+                            write!(f, "invalid value for `{}`: {}", var, message)
+                        }
+                        #error_name::NotUnicode(var) => {
+                            write!(f, "value of `{}` is not valid unicode", var)
+                        }
+                    }
+                }
+            }
+
+            impl ::std::error::Error for #error_name {}
+
+            impl #impl_generics #opt_name #opt_ty_generics #where_clause {
+                #vis fn from_env(prefix: &str) -> Result<Self, #error_name>
+                where
+                    #bounds
+                {
+                    let mut opt = #opt_name { #empty_fields };
+
+                    #var_lookups
+
+                    Ok(opt)
+                }
+
+                #vis fn from_env_iter<I>(prefix: &str, vars: I) -> Result<Self, #error_name>
+                where
+                    I: IntoIterator<Item = (String, String)>,
+                    #bounds
+                {
+                    let mut opt = #opt_name { #empty_fields };
+
+                    for (key, value) in vars {
+                        #var_matches
+                    }
+
+                    Ok(opt)
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
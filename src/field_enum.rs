@@ -0,0 +1,109 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ItemStruct;
+
+use crate::args::Args;
+use crate::fields;
+
+pub fn generate(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if args.field_enum {
+        if let Err(e) = fields::check_variant_idents(&item.fields) {
+            return e.to_compile_error();
+        }
+
+        let vis = &opt_item.vis;
+
+        let opt_name = &opt_item.ident;
+        let (impl_generics, opt_ty_generics, where_clause) = opt_item.generics.split_for_impl();
+
+        let enum_name = format_ident!("{}Field", opt_name);
+
+        let mut variants = TokenStream::new();
+        let mut all_fields = TokenStream::new();
+        let mut names = TokenStream::new();
+        let mut set_checks = TokenStream::new();
+
+        for (i, field) in item.fields.iter().enumerate() {
+            if fields::is_marker(field) {
+                continue;
+            }
+
+            let cfg_attrs = fields::cfg_attrs(field);
+            let field_name = fields::member(i, field);
+            let variant = fields::variant_ident(i, field);
+            // tuple struct fields are named like their variant, since an index
+            // is not a name
+            let field_name_str = match &field.ident {
+                None => variant.to_string(),
+                Some(_) => fields::name(i, field),
+            };
+
+            variants.extend(quote! {
+                #cfg_attrs
+                #variant,
+            });
+
+            all_fields.extend(quote! {
+                #cfg_attrs
+                #enum_name::#variant,
+            });
+
+            names.extend(quote! {
+                #cfg_attrs
+                #enum_name::#variant => #field_name_str,
+            });
+
+            set_checks.extend(quote! {
+                #cfg_attrs
+                #enum_name::#variant => self.#field_name.is_some(),
+            });
+        }
+
+        quote! {
+            #[allow(non_camel_case_types)]
+            #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+            #vis enum #enum_name {
+                #variants
+            }
+
+            impl #enum_name {
+                #vis const FIELDS: &'static [#enum_name] = &[#all_fields];
+
+                #vis fn name(self) -> &'static str {
+                    match self {
+                        #names
+                    }
+                }
+            }
+
+            impl #impl_generics #opt_name #opt_ty_generics #where_clause {
+                #vis fn is_set(&self, field: #enum_name) -> bool {
+                    match field {
+                        #set_checks
+                    }
+                }
+
+                #vis fn set_fields(&self) -> impl Iterator<Item = #enum_name> + '_ {
+                    #enum_name::FIELDS
+                        .iter()
+                        .copied()
+                        .filter(move |field| self.is_set(*field))
+                }
+
+                #vis fn len(&self) -> usize {
+                    self.set_fields().count()
+                }
+
+                #vis fn is_empty(&self) -> bool {
+                    self.set_fields().next().is_none()
+                }
+
+                #vis fn is_complete(&self) -> bool {
+                    #enum_name::FIELDS.iter().all(|field| self.is_set(*field))
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
@@ -0,0 +1,215 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{ItemStruct, Path, PathArguments};
+
+use crate::args::Args;
+use crate::fields;
+
+pub fn generate(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if args.field_mask {
+        let vis = &opt_item.vis;
+
+        let item_name = &item.ident;
+        let (_, item_ty_generics, _) = item.generics.split_for_impl();
+
+        let opt_name = &opt_item.ident;
+        let (impl_generics, opt_ty_generics, where_clause) = opt_item.generics.split_for_impl();
+
+        let error_name = format_ident!("{}MaskError", opt_name);
+
+        let mut field_types = Vec::new();
+        let mut nested_bounds = TokenStream::new();
+        let mut nested_masks = TokenStream::new();
+        let mut nested_fields = TokenStream::new();
+        let mut empty_fields = TokenStream::new();
+        let mut paths = TokenStream::new();
+        let mut path_matches = TokenStream::new();
+        let mut nested_matches = TokenStream::new();
+
+        for (i, field) in item.fields.iter().enumerate() {
+            let cfg_attrs = fields::cfg_attrs(field);
+            let field_name = fields::member(i, field);
+
+            if fields::is_marker(field) {
+                let value = fields::marker_value(field);
+
+                empty_fields.extend(quote! {
+                    #cfg_attrs
+                    #field_name: #value,
+                });
+
+                continue;
+            }
+
+            let path = fields::name(i, field);
+
+            empty_fields.extend(quote! {
+                #cfg_attrs
+                #field_name: None,
+            });
+
+            let ty = &field.ty;
+
+            match fields::args::get(field).nested {
+                // nested opt fields prefix the paths of their own mask
+                Some(nested) => {
+                    let nested_error = nested_error(&nested);
+                    let nested_mask = format_ident!("nested_mask_{}", i);
+
+                    paths.extend(quote! {
+                        #cfg_attrs
+                        {
+                            if let Some(value) = &self.#field_name {
+                                for nested_path in value.field_mask() {
+                                    mask.push(format!("{}.{}", #path, nested_path));
+                                }
+                            }
+                        }
+                    });
+
+                    path_matches.extend(quote! {
+                        #cfg_attrs
+                        #path => opt.#field_name = Some(From::from(source.#field_name.clone())),
+                    });
+
+                    nested_masks.extend(quote! {
+                        #cfg_attrs
+                        let mut #nested_mask: Vec<&str> = Vec::new();
+                    });
+
+                    nested_matches.extend(quote! {
+                        #cfg_attrs
+                        #path => #nested_mask.push(&path[#path.len() + 1..]),
+                    });
+
+                    // the whole field takes precedence over its nested paths
+                    nested_fields.extend(quote! {
+                        #cfg_attrs
+                        {
+                            if !#nested_mask.is_empty() && opt.#field_name.is_none() {
+                                let value = <#nested>::from_field_mask(&#nested_mask, &source.#field_name)
+                                    .map_err(|e| match e {
+                                        #nested_error::UnknownField(nested_path) => {
+                                            #error_name::UnknownField(format!("{}.{}", #path, nested_path))
+                                        }
+                                        #nested_error::NestedPath(nested_path) => {
+                                            #error_name::NestedPath(format!("{}.{}", #path, nested_path))
+                                        }
+                                    })?;
+
+                                opt.#field_name = Some(value);
+                            }
+                        }
+                    });
+
+                    nested_bounds.extend(quote!(#nested: From<#ty>,));
+                }
+                None => {
+                    paths.extend(quote! {
+                        #cfg_attrs
+                        {
+                            if self.#field_name.is_some() {
+                                mask.push(#path.to_string());
+                            }
+                        }
+                    });
+
+                    let value = if fields::is_option(field) && !args.rewrap {
+                        quote!(source.#field_name.clone())
+                    } else {
+                        quote!(Some(source.#field_name.clone()))
+                    };
+
+                    path_matches.extend(quote! {
+                        #cfg_attrs
+                        #path => opt.#field_name = #value,
+                    });
+
+                    nested_matches.extend(quote! {
+                        #cfg_attrs
+                        #path => return Err(#error_name::NestedPath(path.to_string())),
+                    });
+                }
+            }
+
+            field_types.push(ty);
+        }
+
+        quote! {
+            #[derive(Clone, Debug, PartialEq, Eq)]
+            #vis enum #error_name {
+                UnknownField(String),
+                NestedPath(String),
+            }
+
+            impl ::std::fmt::Display for #error_name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    match self {
+                        #error_name::UnknownField(path) => write!(f, "unknown field `{}`", path),
+                        #error_name::NestedPath(path) => {
+                            write!(f, "nested path `{}` is not supported", path)
+                        }
+                    }
+                }
+            }
+
+            impl ::std::error::Error for #error_name {}
+
+            impl #impl_generics #opt_name #opt_ty_generics #where_clause {
+                #vis fn field_mask(&self) -> Vec<String> {
+                    let mut mask = Vec::new();
+
+                    #paths
+
+                    mask
+                }
+
+                #vis fn from_field_mask(
+                    mask: &[&str],
+                    source: &#item_name #item_ty_generics,
+                ) -> Result<Self, #error_name>
+                where
+                    #(#field_types: Clone,)*
+                    #nested_bounds
+                {
+                    let mut opt = #opt_name { #empty_fields };
+
+                    #nested_masks
+
+                    for path in mask {
+                        match *path {
+                            #path_matches
+                            _ => {
+                                let root = path.split('.').next().unwrap_or(path);
+
+                                match root {
+                                    #nested_matches
+                                    _ => return Err(#error_name::UnknownField(path.to_string())),
+                                }
+                            }
+                        }
+                    }
+
+                    #nested_fields
+
+                    Ok(opt)
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
+
+/// Mask error type of a nested opt struct, named after it with a `MaskError`
+/// suffix.
+fn nested_error(nested: &Path) -> Path {
+    let mut error = nested.clone();
+
+    if let Some(segment) = error.segments.last_mut() {
+        segment.ident = format_ident!("{}MaskError", segment.ident);
+        segment.arguments = PathArguments::None;
+    }
+
+    error
+}
@@ -0,0 +1,1094 @@
+use proc_macro2::{Span, TokenStream};
+use quote::ToTokens;
+use syn::parse::{Error, Parse, ParseStream, Result};
+use syn::punctuated::Punctuated;
+use syn::token::{Comma, Eq, Match};
+use syn::{parenthesized, Field, Ident, ItemStruct, LitInt, LitStr, Path};
+
+use crate::args::Args;
+use crate::attrs::generator::is_optfield_attr;
+use crate::error::unexpected;
+use crate::fields;
+
+mod kw {
+    syn::custom_keyword!(fill_default);
+    syn::custom_keyword!(validate);
+    syn::custom_keyword!(readonly);
+    syn::custom_keyword!(merge_if);
+    syn::custom_keyword!(alias);
+    syn::custom_keyword!(env);
+    syn::custom_keyword!(long);
+    syn::custom_keyword!(column);
+    syn::custom_keyword!(roles);
+    syn::custom_keyword!(since);
+    syn::custom_keyword!(until);
+    syn::custom_keyword!(sensitive);
+    syn::custom_keyword!(nested);
+
+    pub mod readonly_sub {
+        syn::custom_keyword!(ignore);
+        syn::custom_keyword!(reject);
+    }
+
+    pub mod match_sub {
+        syn::custom_keyword!(contains);
+        syn::custom_keyword!(prefix);
+        syn::custom_keyword!(min);
+        syn::custom_keyword!(max);
+        syn::custom_keyword!(range);
+    }
+}
+
+/// Arguments given to a field using `#[optfield(...)]`. They are shared by
+/// every optfield attribute of the struct, so each attribute ignores those its
+/// arguments don't use.
+#[derive(Default)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub struct FieldArgs {
+    pub fill_default: bool,
+    pub validate: Option<Path>,
+    pub readonly: Option<Readonly>,
+    pub merge_if: Option<Path>,
+    pub aliases: Vec<LitStr>,
+    pub env: Option<LitStr>,
+    pub long: Option<LitStr>,
+    pub column: Option<LitStr>,
+    pub match_mode: Option<MatchMode>,
+    pub roles: Option<Vec<Ident>>,
+    pub since: Option<u32>,
+    pub until: Option<u32>,
+    pub sensitive: bool,
+    pub nested: Option<Path>,
+}
+
+/// How merge methods handle a readonly field.
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum Readonly {
+    /// Never set the field.
+    Ignore,
+    /// Return an error from the merge method if the opt value is `Some`.
+    Reject,
+}
+
+/// How `matches` compares a field to the original one, instead of equality.
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum MatchMode {
+    /// The original string contains the opt string.
+    Contains,
+    /// The original string starts with the opt string.
+    Prefix,
+    /// The original value is greater than or equal to the opt value.
+    Min,
+    /// The original value is less than or equal to the opt value.
+    Max,
+    /// The original value is in the opt value, an inclusive range.
+    Range,
+}
+
+/// Parser for unordered field args, possibly spread over multiple attributes.
+#[derive(Default)]
+struct FieldArgList {
+    fill_default: Option<Span>,
+    validate: Option<Span>,
+    readonly: Option<Span>,
+    merge_if: Option<Span>,
+    env: Option<Span>,
+    long: Option<Span>,
+    column: Option<Span>,
+    match_mode: Option<Span>,
+    roles: Option<Span>,
+    since: Option<Span>,
+    until: Option<Span>,
+    sensitive: Option<Span>,
+    nested: Option<Span>,
+    args: FieldArgs,
+}
+
+impl FieldArgs {
+    pub fn parse(field: &Field) -> Result<Self> {
+        let mut arg_list = FieldArgList::default();
+
+        for attr in field.attrs.iter().filter(|a| is_optfield_attr(a)) {
+            attr.parse_args_with(|input: ParseStream| arg_list.parse(input))?;
+        }
+
+        if let (Some(since), Some(until)) = (arg_list.args.since, arg_list.args.until) {
+            if since > until {
+                return Err(Error::new(
+                    arg_list.until.unwrap_or_else(Span::call_site),
+                    "until must not be lower than since",
+                ));
+            }
+        }
+
+        Ok(arg_list.args)
+    }
+}
+
+impl FieldArgList {
+    fn parse(&mut self, input: ParseStream) -> Result<()> {
+        while !input.is_empty() {
+            let lookahead = input.lookahead1();
+
+            if lookahead.peek(kw::fill_default) {
+                self.parse_fill_default(input)?;
+            } else if lookahead.peek(kw::validate) {
+                self.parse_validate(input)?;
+            } else if lookahead.peek(kw::readonly) {
+                self.parse_readonly(input)?;
+            } else if lookahead.peek(kw::merge_if) {
+                self.parse_merge_if(input)?;
+            } else if lookahead.peek(kw::alias) {
+                self.parse_alias(input)?;
+            } else if lookahead.peek(kw::env) {
+                self.parse_env(input)?;
+            } else if lookahead.peek(kw::long) {
+                self.parse_long(input)?;
+            } else if lookahead.peek(kw::column) {
+                self.parse_column(input)?;
+            } else if lookahead.peek(Match) {
+                self.parse_match(input)?;
+            } else if lookahead.peek(kw::roles) {
+                self.parse_roles(input)?;
+            } else if lookahead.peek(kw::since) {
+                self.parse_since(input)?;
+            } else if lookahead.peek(kw::until) {
+                self.parse_until(input)?;
+            } else if lookahead.peek(kw::sensitive) {
+                self.parse_sensitive(input)?;
+            } else if lookahead.peek(kw::nested) {
+                self.parse_nested(input)?;
+            } else {
+                return Err(lookahead.error());
+            }
+
+            if input.is_empty() {
+                break;
+            }
+
+            input.parse::<Comma>()?;
+        }
+
+        Ok(())
+    }
+
+    fn parse_fill_default(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(fill_default_span) = self.fill_default {
+            return FieldArgList::already_defined_error(input, "fill_default", fill_default_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::fill_default>()?;
+
+        self.fill_default = Some(span);
+        self.args.fill_default = true;
+
+        Ok(())
+    }
+
+    fn parse_validate(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(validate_span) = self.validate {
+            return FieldArgList::already_defined_error(input, "validate", validate_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::validate>()?;
+        input.parse::<Eq>()?;
+
+        self.validate = Some(span);
+        self.args.validate = Some(input.parse()?);
+
+        Ok(())
+    }
+
+    fn parse_readonly(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(readonly_span) = self.readonly {
+            return FieldArgList::already_defined_error(input, "readonly", readonly_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::readonly>()?;
+
+        self.readonly = Some(span);
+        self.args.readonly = Some(input.parse()?);
+
+        Ok(())
+    }
+
+    fn parse_merge_if(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(merge_if_span) = self.merge_if {
+            return FieldArgList::already_defined_error(input, "merge_if", merge_if_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::merge_if>()?;
+        input.parse::<Eq>()?;
+
+        self.merge_if = Some(span);
+        self.args.merge_if = Some(input.parse()?);
+
+        Ok(())
+    }
+
+    fn parse_alias(&mut self, input: ParseStream) -> Result<()> {
+        input.parse::<kw::alias>()?;
+        input.parse::<Eq>()?;
+
+        self.args.aliases.push(input.parse()?);
+
+        Ok(())
+    }
+
+    fn parse_env(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(env_span) = self.env {
+            return FieldArgList::already_defined_error(input, "env", env_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::env>()?;
+        input.parse::<Eq>()?;
+
+        self.env = Some(span);
+        self.args.env = Some(input.parse()?);
+
+        Ok(())
+    }
+
+    fn parse_long(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(long_span) = self.long {
+            return FieldArgList::already_defined_error(input, "long", long_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::long>()?;
+        input.parse::<Eq>()?;
+
+        self.long = Some(span);
+        self.args.long = Some(input.parse()?);
+
+        Ok(())
+    }
+
+    fn parse_column(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(column_span) = self.column {
+            return FieldArgList::already_defined_error(input, "column", column_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::column>()?;
+        input.parse::<Eq>()?;
+
+        self.column = Some(span);
+        self.args.column = Some(input.parse()?);
+
+        Ok(())
+    }
+
+    fn parse_match(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(match_span) = self.match_mode {
+            return FieldArgList::already_defined_error(input, "match", match_span);
+        }
+
+        let span = input.span();
+        input.parse::<Match>()?;
+        input.parse::<Eq>()?;
+
+        self.match_mode = Some(span);
+        self.args.match_mode = Some(input.parse()?);
+
+        Ok(())
+    }
+
+    fn parse_roles(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(roles_span) = self.roles {
+            return FieldArgList::already_defined_error(input, "roles", roles_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::roles>()?;
+        input.parse::<Eq>()?;
+
+        let content;
+        parenthesized!(content in input);
+        let roles = Punctuated::<Ident, Comma>::parse_terminated(&content)?;
+
+        self.roles = Some(span);
+        self.args.roles = Some(roles.into_iter().collect());
+
+        Ok(())
+    }
+
+    fn parse_since(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(since_span) = self.since {
+            return FieldArgList::already_defined_error(input, "since", since_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::since>()?;
+        input.parse::<Eq>()?;
+
+        self.since = Some(span);
+        self.args.since = Some(input.parse::<LitInt>()?.base10_parse()?);
+
+        Ok(())
+    }
+
+    fn parse_until(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(until_span) = self.until {
+            return FieldArgList::already_defined_error(input, "until", until_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::until>()?;
+        input.parse::<Eq>()?;
+
+        self.until = Some(span);
+        self.args.until = Some(input.parse::<LitInt>()?.base10_parse()?);
+
+        Ok(())
+    }
+
+    fn parse_sensitive(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(sensitive_span) = self.sensitive {
+            return FieldArgList::already_defined_error(input, "sensitive", sensitive_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::sensitive>()?;
+
+        self.sensitive = Some(span);
+        self.args.sensitive = true;
+
+        Ok(())
+    }
+
+    fn parse_nested(&mut self, input: ParseStream) -> Result<()> {
+        if let Some(nested_span) = self.nested {
+            return FieldArgList::already_defined_error(input, "nested", nested_span);
+        }
+
+        let span = input.span();
+        input.parse::<kw::nested>()?;
+        input.parse::<Eq>()?;
+
+        self.nested = Some(span);
+        self.args.nested = Some(input.parse()?);
+
+        Ok(())
+    }
+
+    fn already_defined_error(
+        input: ParseStream,
+        arg_name: &'static str,
+        prev_span: Span,
+    ) -> Result<()> {
+        let mut e = input.error(format!("{} already defined", arg_name));
+        e.combine(Error::new(prev_span, format!("{} defined here", arg_name)));
+        Err(e)
+    }
+}
+
+impl Parse for Readonly {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Eq) {
+            input.parse::<Eq>()?;
+
+            let lookahead = input.lookahead1();
+
+            if lookahead.peek(kw::readonly_sub::ignore) {
+                input.parse::<kw::readonly_sub::ignore>()?;
+
+                Ok(Readonly::Ignore)
+            } else if lookahead.peek(kw::readonly_sub::reject) {
+                input.parse::<kw::readonly_sub::reject>()?;
+
+                Ok(Readonly::Reject)
+            } else {
+                Err(lookahead.error())
+            }
+        } else {
+            Ok(Readonly::Ignore)
+        }
+    }
+}
+
+impl Parse for MatchMode {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+
+        if lookahead.peek(kw::match_sub::contains) {
+            input.parse::<kw::match_sub::contains>()?;
+
+            Ok(MatchMode::Contains)
+        } else if lookahead.peek(kw::match_sub::prefix) {
+            input.parse::<kw::match_sub::prefix>()?;
+
+            Ok(MatchMode::Prefix)
+        } else if lookahead.peek(kw::match_sub::min) {
+            input.parse::<kw::match_sub::min>()?;
+
+            Ok(MatchMode::Min)
+        } else if lookahead.peek(kw::match_sub::max) {
+            input.parse::<kw::match_sub::max>()?;
+
+            Ok(MatchMode::Max)
+        } else if lookahead.peek(kw::match_sub::range) {
+            input.parse::<kw::match_sub::range>()?;
+
+            Ok(MatchMode::Range)
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+/// Field args of an already checked item.
+pub fn get(field: &Field) -> FieldArgs {
+    FieldArgs::parse(field)
+        .unwrap_or_else(|e| panic!("{}", unexpected("parsing field args".to_string(), e)))
+}
+
+/// Field args of an already checked item, as used by the attribute with the
+/// given args.
+pub fn get_for(field: &Field, args: &Args) -> FieldArgs {
+    used(get(field), args)
+}
+
+/// Leaves out the field args changing the opt field type when the attribute
+/// doesn't use them.
+fn used(mut field_args: FieldArgs, args: &Args) -> FieldArgs {
+    if !args.matches && matches!(field_args.match_mode, Some(MatchMode::Range)) {
+        field_args.match_mode = None;
+    }
+
+    if !args.field_mask {
+        field_args.nested = None;
+    }
+
+    field_args
+}
+
+/// Parses the field args of all fields, combining all errors.
+pub fn check(item: &ItemStruct) -> Result<()> {
+    let mut error: Option<Error> = None;
+
+    let mut add_error = |e: Error| match &mut error {
+        None => error = Some(e),
+        Some(error) => error.combine(e),
+    };
+
+    let mut keys: Vec<String> = item
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| fields::name(i, field))
+        .collect();
+
+    for field in item.fields.iter() {
+        match FieldArgs::parse(field) {
+            Ok(args) => {
+                for alias in args.aliases {
+                    if keys.contains(&alias.value()) {
+                        add_error(Error::new(
+                            alias.span(),
+                            format!("key `{}` already used", alias.value()),
+                        ));
+                    } else {
+                        keys.push(alias.value());
+                    }
+                }
+            }
+            Err(e) => add_error(e),
+        }
+    }
+
+    match error {
+        None => Ok(()),
+        Some(e) => Err(e),
+    }
+}
+
+/// Checks the fields whose opt values don't hold the original field type in
+/// this attribute, nested fields and `match = range` fields, which only some
+/// arguments support.
+pub fn check_opt_types(item: &ItemStruct, args: &Args) -> Result<()> {
+    let enabled = [
+        (args.merge.is_some(), "merge_fn"),
+        (args.from || args.from_ref, "from"),
+        (args.three_way.is_some(), "three_way"),
+        (args.fill.is_some(), "fill_fn"),
+        (args.merge_ref.is_some(), "merge_ref_fn"),
+        (args.with.is_some(), "with_fn"),
+        (args.merge_arc.is_some(), "merge_arc_fn"),
+        (args.try_merge.is_some(), "try_merge_fn"),
+        (args.update_enum.is_some(), "update_enum"),
+        (args.str_access, "str_access"),
+        (args.from_env, "from_env"),
+        (args.from_args, "from_args"),
+        (args.query, "query"),
+        (args.sql.is_some(), "sql"),
+        (args.matches, "matches"),
+        (args.field_mask, "field_mask"),
+        (args.debug, "debug"),
+        (args.views, "views"),
+        (args.owned, "owned"),
+    ];
+
+    for field in item.fields.iter() {
+        let field_args = used(FieldArgs::parse(field)?, args);
+
+        let (span, kind, supported): (TokenStream, _, &[_]) = match &field_args {
+            FieldArgs {
+                nested: Some(nested),
+                ..
+            } => {
+                if fields::is_option(field) || fields::is_marker(field) {
+                    return Err(Error::new_spanned(
+                        nested,
+                        "nested fields can't be `Option` or marker fields",
+                    ));
+                }
+
+                (
+                    nested.to_token_stream(),
+                    "nested fields",
+                    &["from", "field_mask"],
+                )
+            }
+            FieldArgs {
+                match_mode: Some(MatchMode::Range),
+                ..
+            } => (
+                field.to_token_stream(),
+                "range matched fields",
+                &["matches"],
+            ),
+            _ => continue,
+        };
+
+        let conflict = enabled
+            .iter()
+            .find(|(on, arg_name)| *on && !supported.contains(arg_name));
+
+        if let Some((_, arg_name)) = conflict {
+            return Err(Error::new_spanned(
+                span,
+                format!("{} can't be combined with {}", kind, arg_name),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes `#[optfield(...)]` attributes from the item fields, since they are
+/// not real attributes.
+pub fn remove_attrs(item: &mut ItemStruct) {
+    for field in item.fields.iter_mut() {
+        field.attrs.retain(|a| !is_optfield_attr(a));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use quote::quote;
+    use syn::parse_quote;
+
+    use crate::test_util::*;
+
+    #[test]
+    fn parse_no_args() {
+        let field = parse_field(quote! {
+            #[some_attr]
+            field: String
+        });
+
+        assert_eq!(FieldArgs::parse(&field).unwrap(), FieldArgs::default());
+    }
+
+    #[test]
+    fn parse_fill_default() {
+        let field = parse_field(quote! {
+            #[optfield(fill_default)]
+            field: String
+        });
+
+        assert!(FieldArgs::parse(&field).unwrap().fill_default);
+    }
+
+    #[test]
+    #[should_panic(expected = "fill_default already defined")]
+    fn duplicate_fill_default_panics() {
+        let field = parse_field(quote! {
+            #[optfield(fill_default)]
+            #[optfield(fill_default)]
+            field: String
+        });
+
+        FieldArgs::parse(&field).unwrap();
+    }
+
+    #[test]
+    fn parse_validate() {
+        let field = parse_field(quote! {
+            #[optfield(fill_default, validate = path::to::check)]
+            field: String
+        });
+
+        let args = FieldArgs::parse(&field).unwrap();
+
+        assert!(args.fill_default);
+        assert_eq!(
+            args.validate,
+            Some(syn::parse2(quote!(path::to::check)).unwrap())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "validate already defined")]
+    fn duplicate_validate_panics() {
+        let field = parse_field(quote! {
+            #[optfield(validate = check)]
+            #[optfield(validate = other_check)]
+            field: String
+        });
+
+        FieldArgs::parse(&field).unwrap();
+    }
+
+    #[test]
+    fn parse_readonly() {
+        let cases = vec![
+            (quote! {#[optfield(readonly)]}, Readonly::Ignore),
+            (quote! {#[optfield(readonly = ignore)]}, Readonly::Ignore),
+            (quote! {#[optfield(readonly = reject)]}, Readonly::Reject),
+        ];
+
+        for (attr, readonly) in cases {
+            let field = parse_field(quote! {
+                #attr
+                field: String
+            });
+
+            assert_eq!(FieldArgs::parse(&field).unwrap().readonly, Some(readonly));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "expected `ignore` or `reject`")]
+    fn unknown_readonly_panics() {
+        let field = parse_field(quote! {
+            #[optfield(readonly = overwrite)]
+            field: String
+        });
+
+        FieldArgs::parse(&field).unwrap();
+    }
+
+    #[test]
+    fn parse_merge_if() {
+        let field = parse_field(quote! {
+            #[optfield(merge_if = path::to::newer)]
+            field: u32
+        });
+
+        assert_eq!(
+            FieldArgs::parse(&field).unwrap().merge_if,
+            Some(syn::parse2(quote!(path::to::newer)).unwrap())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "merge_if already defined")]
+    fn duplicate_merge_if_panics() {
+        let field = parse_field(quote! {
+            #[optfield(merge_if = newer, merge_if = older)]
+            field: u32
+        });
+
+        FieldArgs::parse(&field).unwrap();
+    }
+
+    #[test]
+    fn parse_aliases() {
+        let field = parse_field(quote! {
+            #[optfield(alias = "first", alias = "second")]
+            #[optfield(alias = "third")]
+            field: String
+        });
+
+        let aliases: Vec<_> = FieldArgs::parse(&field)
+            .unwrap()
+            .aliases
+            .iter()
+            .map(LitStr::value)
+            .collect();
+
+        assert_eq!(aliases, vec!["first", "second", "third"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "key `second` already used")]
+    fn alias_clashing_with_field_name_panics() {
+        let item = parse_item(quote! {
+            struct S {
+                #[optfield(alias = "second")]
+                first: String,
+                second: String
+            }
+        });
+
+        check(&item).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "key `name` already used")]
+    fn duplicate_alias_panics() {
+        let item = parse_item(quote! {
+            struct S {
+                #[optfield(alias = "name")]
+                first: String,
+                #[optfield(alias = "name")]
+                second: String
+            }
+        });
+
+        check(&item).unwrap();
+    }
+
+    #[test]
+    fn parse_env() {
+        let field = parse_field(quote! {
+            #[optfield(env = "DATABASE_URL")]
+            field: String
+        });
+
+        let env = FieldArgs::parse(&field).unwrap().env.unwrap();
+
+        assert_eq!(env.value(), "DATABASE_URL");
+    }
+
+    #[test]
+    #[should_panic(expected = "env already defined")]
+    fn duplicate_env_panics() {
+        let field = parse_field(quote! {
+            #[optfield(env = "FIRST")]
+            #[optfield(env = "SECOND")]
+            field: String
+        });
+
+        FieldArgs::parse(&field).unwrap();
+    }
+
+    #[test]
+    fn parse_long() {
+        let field = parse_field(quote! {
+            #[optfield(long = "db-url")]
+            field: String
+        });
+
+        let long = FieldArgs::parse(&field).unwrap().long.unwrap();
+
+        assert_eq!(long.value(), "db-url");
+    }
+
+    #[test]
+    #[should_panic(expected = "long already defined")]
+    fn duplicate_long_panics() {
+        let field = parse_field(quote! {
+            #[optfield(long = "first", long = "second")]
+            field: String
+        });
+
+        FieldArgs::parse(&field).unwrap();
+    }
+
+    #[test]
+    fn parse_column() {
+        let field = parse_field(quote! {
+            #[optfield(column = "user_name")]
+            field: String
+        });
+
+        let column = FieldArgs::parse(&field).unwrap().column.unwrap();
+
+        assert_eq!(column.value(), "user_name");
+    }
+
+    #[test]
+    #[should_panic(expected = "column already defined")]
+    fn duplicate_column_panics() {
+        let field = parse_field(quote! {
+            #[optfield(column = "first")]
+            #[optfield(column = "second")]
+            field: String
+        });
+
+        FieldArgs::parse(&field).unwrap();
+    }
+
+    #[test]
+    fn parse_match() {
+        let cases = vec![
+            (quote! {#[optfield(match = contains)]}, MatchMode::Contains),
+            (quote! {#[optfield(match = prefix)]}, MatchMode::Prefix),
+            (quote! {#[optfield(match = min)]}, MatchMode::Min),
+            (quote! {#[optfield(match = max)]}, MatchMode::Max),
+            (quote! {#[optfield(match = range)]}, MatchMode::Range),
+        ];
+
+        for (attr, match_mode) in cases {
+            let field = parse_field(quote! {
+                #attr
+                field: String
+            });
+
+            assert_eq!(
+                FieldArgs::parse(&field).unwrap().match_mode,
+                Some(match_mode)
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "match already defined")]
+    fn duplicate_match_panics() {
+        let field = parse_field(quote! {
+            #[optfield(match = min, match = max)]
+            field: u32
+        });
+
+        FieldArgs::parse(&field).unwrap();
+    }
+
+    #[test]
+    fn parse_roles() {
+        let field = parse_field(quote! {
+            #[optfield(roles = (Admin, Owner))]
+            field: String
+        });
+
+        let roles = FieldArgs::parse(&field).unwrap().roles.unwrap();
+
+        assert_eq!(roles, vec!["Admin", "Owner"]);
+    }
+
+    #[test]
+    fn parse_empty_roles() {
+        let field = parse_field(quote! {
+            #[optfield(roles = ())]
+            field: String
+        });
+
+        assert_eq!(FieldArgs::parse(&field).unwrap().roles, Some(Vec::new()));
+    }
+
+    #[test]
+    #[should_panic(expected = "roles already defined")]
+    fn duplicate_roles_panics() {
+        let field = parse_field(quote! {
+            #[optfield(roles = (Admin))]
+            #[optfield(roles = (Owner))]
+            field: String
+        });
+
+        FieldArgs::parse(&field).unwrap();
+    }
+
+    #[test]
+    fn parse_since_until() {
+        let field = parse_field(quote! {
+            #[optfield(since = 3, until = 5)]
+            field: String
+        });
+
+        let args = FieldArgs::parse(&field).unwrap();
+
+        assert_eq!(args.since, Some(3));
+        assert_eq!(args.until, Some(5));
+    }
+
+    #[test]
+    #[should_panic(expected = "until must not be lower than since")]
+    fn until_lower_than_since_panics() {
+        let field = parse_field(quote! {
+            #[optfield(since = 5, until = 3)]
+            field: String
+        });
+
+        FieldArgs::parse(&field).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "since already defined")]
+    fn duplicate_since_panics() {
+        let field = parse_field(quote! {
+            #[optfield(since = 1)]
+            #[optfield(since = 2)]
+            field: String
+        });
+
+        FieldArgs::parse(&field).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "until already defined")]
+    fn duplicate_until_panics() {
+        let field = parse_field(quote! {
+            #[optfield(until = 1, until = 2)]
+            field: String
+        });
+
+        FieldArgs::parse(&field).unwrap();
+    }
+
+    #[test]
+    fn parse_sensitive() {
+        let field = parse_field(quote! {
+            #[optfield(sensitive)]
+            field: String
+        });
+
+        assert!(FieldArgs::parse(&field).unwrap().sensitive);
+    }
+
+    #[test]
+    #[should_panic(expected = "sensitive already defined")]
+    fn duplicate_sensitive_panics() {
+        let field = parse_field(quote! {
+            #[optfield(sensitive, sensitive)]
+            field: String
+        });
+
+        FieldArgs::parse(&field).unwrap();
+    }
+
+    #[test]
+    fn parse_nested() {
+        let field = parse_field(quote! {
+            #[optfield(nested = path::to::AddressOpt)]
+            field: Address
+        });
+
+        let expected: Path = parse_quote!(path::to::AddressOpt);
+
+        assert_eq!(FieldArgs::parse(&field).unwrap().nested, Some(expected));
+    }
+
+    #[test]
+    #[should_panic(expected = "nested already defined")]
+    fn duplicate_nested_panics() {
+        let field = parse_field(quote! {
+            #[optfield(nested = AddressOpt)]
+            #[optfield(nested = OtherOpt)]
+            field: Address
+        });
+
+        FieldArgs::parse(&field).unwrap();
+    }
+
+    #[test]
+    fn nested_option_is_error() {
+        let (item, args) = parse_item_and_args(
+            quote! {
+                struct S {
+                    #[optfield(nested = AddressOpt)]
+                    field: Option<Address>
+                }
+            },
+            quote! {
+                Opt,
+                field_mask
+            },
+        );
+
+        let error = check_opt_types(&item, &args).unwrap_err();
+
+        assert!(error.to_string().contains("can't be `Option`"));
+    }
+
+    #[test]
+    fn nested_conflicts_are_errors() {
+        let (item, args) = parse_item_and_args(
+            quote! {
+                struct S {
+                    #[optfield(nested = AddressOpt)]
+                    field: Address
+                }
+            },
+            quote! {
+                Opt,
+                field_mask,
+                merge_fn
+            },
+        );
+
+        let error = check_opt_types(&item, &args).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "nested fields can't be combined with merge_fn"
+        );
+    }
+
+    #[test]
+    fn range_conflicts_are_errors() {
+        let (item, args) = parse_item_and_args(
+            quote! {
+                struct S {
+                    #[optfield(match = range)]
+                    field: u32
+                }
+            },
+            quote! {
+                Opt,
+                matches,
+                from
+            },
+        );
+
+        let error = check_opt_types(&item, &args).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "range matched fields can't be combined with from"
+        );
+    }
+
+    #[test]
+    fn check_combines_errors() {
+        let item = parse_item(quote! {
+            struct S {
+                #[optfield(unknown)]
+                first: String,
+                #[optfield(fill_default, fill_default)]
+                second: i32
+            }
+        });
+
+        let error = check(&item).unwrap_err();
+
+        assert_eq!(error.into_iter().count(), 3);
+    }
+
+    #[test]
+    fn remove_field_attrs() {
+        let mut item = parse_item(quote! {
+            struct S {
+                #[optfield(fill_default)]
+                #[other]
+                field: String
+            }
+        });
+
+        remove_attrs(&mut item);
+
+        let expected = parse_attrs(quote! {
+            #[other]
+        });
+
+        assert_eq!(item.fields.iter().next().unwrap().attrs, expected);
+    }
+}
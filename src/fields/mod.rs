@@ -1,12 +1,21 @@
-use quote::quote;
-use syn::{parse2, Field, Fields, ItemStruct, Path, Type, TypePath};
+use std::collections::HashMap;
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    ext::IdentExt, parse2, Attribute, Field, Fields, GenericArgument, Ident, Index, ItemStruct,
+    Path, PathArguments, Type, TypePath,
+};
 
 use crate::args::Args;
 use crate::error::unexpected;
 
+pub mod args;
 mod attrs;
 
 const OPTION: &str = "Option";
+const PHANTOM_DATA: &str = "PhantomData";
+const CFG: &str = "cfg";
 
 /// Wraps item fields in Option.
 pub fn generate(item: &ItemStruct, args: &Args) -> Fields {
@@ -15,17 +24,40 @@ pub fn generate(item: &ItemStruct, args: &Args) -> Fields {
     let mut fields = item.fields.clone();
 
     for field in fields.iter_mut() {
+        let field_args = args::get_for(field, args);
+
         field.attrs = attrs::generate(field, args);
         attrs::generate(field, args);
 
-        if is_option(field) && !args.rewrap {
+        if is_marker(field) {
             continue;
         }
 
-        let ty = &field.ty;
+        let opt_type = match field_args {
+            args::FieldArgs {
+                nested: Some(nested),
+                ..
+            } => quote! {
+                Option<#nested>
+            },
+            args::FieldArgs {
+                match_mode: Some(args::MatchMode::Range),
+                ..
+            } => {
+                let value_type = value_type(field, args);
+
+                quote! {
+                    Option<::std::ops::RangeInclusive<#value_type>>
+                }
+            }
+            _ if is_option(field) && !args.rewrap => continue,
+            _ => {
+                let ty = &field.ty;
 
-        let opt_type = quote! {
-            Option<#ty>
+                quote! {
+                    Option<#ty>
+                }
+            }
         };
 
         field.ty = parse2(opt_type).unwrap_or_else(|e| {
@@ -55,6 +87,160 @@ pub fn is_option(field: &Field) -> bool {
     }
 }
 
+/// Whether the field is a `PhantomData` or `()` marker, which holds no data and
+/// is copied to the opt struct unwrapped.
+pub fn is_marker(field: &Field) -> bool {
+    match &field.ty {
+        Type::Path(TypePath {
+            path: Path { segments, .. },
+            ..
+        }) => {
+            if let Some(segment) = segments.last() {
+                segment.ident == PHANTOM_DATA
+            } else {
+                false
+            }
+        }
+        Type::Tuple(tuple) => tuple.elems.is_empty(),
+        _ => false,
+    }
+}
+
+/// Value of a marker field.
+pub fn marker_value(field: &Field) -> TokenStream {
+    match &field.ty {
+        Type::Tuple(_) => quote!(()),
+        _ => quote!(::std::marker::PhantomData),
+    }
+}
+
+/// Field name or, for tuple structs, field index.
+pub fn member(index: usize, field: &Field) -> TokenStream {
+    match &field.ident {
+        // means that original item is a tuple struct
+        None => {
+            let index = Index::from(index);
+
+            quote!(#index)
+        }
+        Some(ident) => quote!(#ident),
+    }
+}
+
+/// Field name as written in the original struct, or its index for tuple
+/// structs.
+pub fn name(index: usize, field: &Field) -> String {
+    match &field.ident {
+        None => index.to_string(),
+        Some(ident) => ident.unraw().to_string(),
+    }
+}
+
+/// Enum variant identifier for a field: `my_field` becomes `MyField` and tuple
+/// struct fields become `_0`, `_1`... Names left empty or starting with a digit,
+/// like `__` or `_1`, get a `Field` prefix.
+pub fn variant_ident(index: usize, field: &Field) -> Ident {
+    match &field.ident {
+        None => format_ident!("_{}", index),
+        Some(ident) => {
+            let variant: String = ident
+                .unraw()
+                .to_string()
+                .split('_')
+                .map(|word| {
+                    let mut chars = word.chars();
+
+                    match chars.next() {
+                        None => String::new(),
+                        Some(first) => first.to_uppercase().chain(chars).collect(),
+                    }
+                })
+                .collect();
+
+            match variant.chars().next() {
+                Some(first) if !first.is_ascii_digit() => format_ident!("{}", variant),
+                _ => format_ident!("Field{}", variant),
+            }
+        }
+    }
+}
+
+/// Checks that no two fields share an enum variant identifier, like `foo_1`
+/// and `foo1`. Marker fields have no variant.
+pub fn check_variant_idents(fields: &Fields) -> syn::Result<()> {
+    let mut variants: HashMap<String, String> = HashMap::new();
+
+    for (i, field) in fields.iter().enumerate().filter(|(_, f)| !is_marker(f)) {
+        let variant = variant_ident(i, field).to_string();
+
+        if let Some(other) = variants.get(&variant) {
+            return Err(syn::Error::new_spanned(
+                field,
+                format!(
+                    "field `{}` has the same variant `{}` as field `{}`",
+                    name(i, field),
+                    variant,
+                    other
+                ),
+            ));
+        }
+
+        variants.insert(variant, name(i, field));
+    }
+
+    Ok(())
+}
+
+/// The `cfg` attributes of a field, to be repeated wherever it is used.
+pub fn cfg_attrs(field: &Field) -> TokenStream {
+    let mut cfg_attrs = TokenStream::new();
+
+    for attr in field.attrs.iter() {
+        if is_cfg_attr(attr) {
+            attr.to_tokens(&mut cfg_attrs);
+        }
+    }
+
+    cfg_attrs
+}
+
+pub fn is_cfg_attr(attr: &Attribute) -> bool {
+    attr.path().is_ident(CFG)
+}
+
+/// Type of the value held by the opt field when it is `Some`.
+pub fn value_type(field: &Field, args: &Args) -> Type {
+    if is_option(field) && !args.rewrap {
+        if let Some(inner) = option_inner(&field.ty) {
+            return inner.clone();
+        }
+    }
+
+    field.ty.clone()
+}
+
+/// Type to parse from a string to set a field, and the expression wrapping the
+/// parsed `value` into the opt field value. Rewrapped `Option` fields parse
+/// their inner type, since a string can't express `None`.
+pub fn parsed_type(field: &Field, args: &Args) -> (Type, TokenStream) {
+    match option_inner(&field.ty) {
+        Some(inner) if is_option(field) && args.rewrap => (inner.clone(), quote!(Some(value))),
+        _ => (value_type(field, args), quote!(value)),
+    }
+}
+
+pub fn option_inner(ty: &Type) -> Option<&Type> {
+    if let Type::Path(TypePath { path, .. }) = ty {
+        if let PathArguments::AngleBracketed(generic_args) = &path.segments.last()?.arguments {
+            if let Some(GenericArgument::Type(inner)) = generic_args.args.first() {
+                return Some(inner);
+            }
+        }
+    }
+
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,6 +265,206 @@ mod tests {
         assert!(is_option(&field));
     }
 
+    #[test]
+    fn variant_ident_collision_is_error() {
+        let item = parse_item(quote! {
+            struct S {
+                foo_1: String,
+                marker: PhantomData<()>,
+                foo1: String
+            }
+        });
+
+        let error = check_variant_idents(&item.fields).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "field `foo1` has the same variant `Foo1` as field `foo_1`"
+        );
+    }
+
+    #[test]
+    fn markers() {
+        let item = parse_item(quote! {
+            struct S<T> {
+                phantom: PhantomData<T>,
+                std_phantom: ::std::marker::PhantomData<fn() -> T>,
+                unit: (),
+                tuple: (T,),
+                text: String,
+            }
+        });
+
+        let markers: Vec<bool> = item.fields.iter().map(is_marker).collect();
+
+        assert_eq!(markers, vec![true, true, true, false, false]);
+
+        let values: Vec<String> = item
+            .fields
+            .iter()
+            .take(3)
+            .map(|f| marker_value(f).to_string())
+            .collect();
+
+        assert_eq!(
+            values,
+            vec![
+                quote!(::std::marker::PhantomData).to_string(),
+                quote!(::std::marker::PhantomData).to_string(),
+                quote!(()).to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn names() {
+        let item = parse_item(quote! {
+            struct S {
+                text: String,
+                r#type: i32,
+            }
+        });
+
+        let names: Vec<String> = item
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| name(i, f))
+            .collect();
+
+        assert_eq!(names, vec!["text", "type"]);
+
+        let item = parse_item(quote! {
+            struct S(String, i32);
+        });
+
+        let names: Vec<String> = item
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| name(i, f))
+            .collect();
+
+        assert_eq!(names, vec!["0", "1"]);
+    }
+
+    #[test]
+    fn variant_idents() {
+        let item = parse_item(quote! {
+            struct S {
+                text: String,
+                long_field_name: i32,
+                r#type: u8,
+                _private: u8,
+                _1: u8,
+                __: u8,
+            }
+        });
+
+        let variants: Vec<Ident> = item
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| variant_ident(i, f))
+            .collect();
+
+        assert_eq!(
+            variants,
+            vec![
+                "Text",
+                "LongFieldName",
+                "Type",
+                "Private",
+                "Field1",
+                "Field"
+            ]
+        );
+
+        let item = parse_item(quote! {
+            struct S(String, i32);
+        });
+
+        let variants: Vec<Ident> = item
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| variant_ident(i, f))
+            .collect();
+
+        assert_eq!(variants, vec!["_0", "_1"]);
+    }
+
+    #[test]
+    fn value_types() {
+        let (item, args) = parse_item_and_args(
+            quote! {
+                struct S<T> {
+                    text: String,
+                    number: Option<i128>,
+                    generic: T,
+                    optional_generic: Option<T>
+                }
+            },
+            quote! {
+                Opt
+            },
+        );
+
+        let expected_types =
+            parse_types(vec![quote! {String}, quote! {i128}, quote! {T}, quote! {T}]);
+
+        let value_types: Vec<Type> = item.fields.iter().map(|f| value_type(f, &args)).collect();
+
+        assert_eq!(value_types, expected_types);
+    }
+
+    #[test]
+    fn value_types_with_rewrap() {
+        let (item, args) = parse_item_and_args(
+            quote! {
+                struct S<T> {
+                    number: Option<i128>,
+                    generic: T
+                }
+            },
+            quote! {
+                Opt,
+                rewrap
+            },
+        );
+
+        let expected_types = parse_types(vec![quote! {Option<i128>}, quote! {T}]);
+
+        let value_types: Vec<Type> = item.fields.iter().map(|f| value_type(f, &args)).collect();
+
+        assert_eq!(value_types, expected_types);
+    }
+
+    #[test]
+    fn parsed_types_with_rewrap() {
+        let (item, args) = parse_item_and_args(
+            quote! {
+                struct S {
+                    number: Option<i128>,
+                    text: String
+                }
+            },
+            quote! {
+                Opt,
+                rewrap
+            },
+        );
+
+        let expected_types = parse_types(vec![quote! {i128}, quote! {String}]);
+
+        let (parsed_types, values): (Vec<Type>, Vec<TokenStream>) =
+            item.fields.iter().map(|f| parsed_type(f, &args)).unzip();
+
+        assert_eq!(parsed_types, expected_types);
+        assert_eq!(values[0].to_string(), quote!(Some(value)).to_string());
+        assert_eq!(values[1].to_string(), quote!(value).to_string());
+    }
+
     #[test]
     fn without_rewrap() {
         let (item, args) = parse_item_and_args(
@@ -107,6 +493,33 @@ mod tests {
         assert_eq!(field_types(generated), expected_types);
     }
 
+    #[test]
+    fn markers_unwrapped() {
+        let (item, args) = parse_item_and_args(
+            quote! {
+                struct S<T> {
+                    phantom: PhantomData<T>,
+                    unit: (),
+                    number: Option<i32>
+                }
+            },
+            quote! {
+                Opt,
+                rewrap
+            },
+        );
+
+        let expected_types = parse_types(vec![
+            quote! {PhantomData<T>},
+            quote! {()},
+            quote! {Option<Option<i32>>},
+        ]);
+
+        let generated = generate(&item, &args);
+
+        assert_eq!(field_types(generated), expected_types);
+    }
+
     #[test]
     fn with_rewrap() {
         let (item, args) = parse_item_and_args(
@@ -1,72 +1,133 @@
 use proc_macro2::TokenStream;
-use quote::{quote, ToTokens};
-use syn::{Fields, Index, ItemStruct};
+use quote::quote;
+use syn::{parse_quote, Fields, ItemStruct};
 
 use crate::args::Args;
 use crate::fields;
-
-const CFG: &str = "cfg";
+use crate::generics;
+use crate::owned;
 
 pub fn generate(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
-    if args.from {
-        let item_name = &item.ident;
-        let item_generics = &item.generics;
+    let mut tokens = TokenStream::new();
+
+    let item_name = &item.ident;
+
+    let opt_name = &opt_item.ident;
+    let (_, opt_ty_generics, _) = opt_item.generics.split_for_impl();
+    let (_, item_ty_generics, _) = item.generics.split_for_impl();
 
-        let opt_name = &opt_item.ident;
-        let opt_generics = &opt_item.generics;
+    // the opt struct may lack some of the original's parameters, or need bounds
+    // the original doesn't have
+    let mut generics = generics::merged(&item.generics, &opt_item.generics);
 
-        let fields = field_bindings(&item.fields, args);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
 
-        quote! {
-            impl#opt_generics From<#item_name#item_generics> for #opt_name#opt_generics {
-                fn from(item: #item_name#item_generics) -> #opt_name#opt_generics {
+    if args.from {
+        let fields = field_bindings(&item.fields, &opt_item.fields, args, false);
+
+        tokens.extend(quote! {
+            impl #impl_generics From<#item_name #item_ty_generics> for #opt_name #opt_ty_generics #where_clause {
+                fn from(item: #item_name #item_ty_generics) -> #opt_name #opt_ty_generics {
                     #opt_name {
                         #fields
                     }
                 }
             }
+        });
+    }
+
+    if args.from_ref {
+        let fields = field_bindings(&item.fields, &opt_item.fields, args, true);
+
+        let ref_where_clause = generics.make_where_clause();
+
+        // bounds can't be cfg'd out along with their fields
+        for field in item
+            .fields
+            .iter()
+            .filter(|f| fields::cfg_attrs(f).is_empty() && !fields::is_marker(f))
+        {
+            let ty = &field.ty;
+
+            ref_where_clause.predicates.push(parse_quote!(#ty: Clone));
         }
-    } else {
-        TokenStream::new()
+
+        let (impl_generics, _, ref_where_clause) = generics.split_for_impl();
+
+        tokens.extend(quote! {
+            impl #impl_generics From<&#item_name #item_ty_generics> for #opt_name #opt_ty_generics #ref_where_clause {
+                fn from(item: &#item_name #item_ty_generics) -> #opt_name #opt_ty_generics {
+                    #opt_name {
+                        #fields
+                    }
+                }
+            }
+        });
     }
+
+    tokens
 }
 
-fn field_bindings(fields: &Fields, args: &Args) -> TokenStream {
+fn field_bindings(fields: &Fields, opt_fields: &Fields, args: &Args, by_ref: bool) -> TokenStream {
     let mut tokens = TokenStream::new();
 
     for (i, field) in fields.iter().enumerate() {
-        let mut cfg_attrs = TokenStream::new();
+        let cfg_attrs = fields::cfg_attrs(field);
+        let field_name = fields::member(i, field);
 
-        for attr in field.attrs.iter() {
-            if attr.path().is_ident(CFG) {
-                attr.to_tokens(&mut cfg_attrs);
-            }
-        }
+        if fields::is_marker(field) {
+            let value = fields::marker_value(field);
+
+            tokens.extend(quote! {
+                #cfg_attrs
+                #field_name: #value,
+            });
 
-        let field_name = match &field.ident {
-            // means that original item is a tuple struct
-            None => {
-                let index = Index::from(i);
+            continue;
+        }
 
-                quote!(#index)
+        let value = if args.owned && owned::is_borrowed(field) {
+            if fields::is_option(field) {
+                quote!(item.#field_name.map(|value| value.to_owned()))
+            } else {
+                quote!(item.#field_name.to_owned())
             }
-            Some(ident) => quote!(#ident),
+        } else if by_ref {
+            quote!(item.#field_name.clone())
+        } else {
+            quote!(item.#field_name)
+        };
+
+        let value = if fields::args::get_for(field, args).nested.is_some() {
+            quote!(From::from(#value))
+        } else {
+            value
         };
 
         let field_tokens = if fields::is_option(field) && !args.rewrap {
             quote! {
                 #cfg_attrs
-                #field_name: item.#field_name,
+                #field_name: #value,
             }
         } else {
             quote! {
                 #cfg_attrs
-                #field_name: Some(item.#field_name),
+                #field_name: Some(#value),
             }
         };
 
         tokens.extend(field_tokens);
     }
 
+    // fields only the opt struct has, like the unused generics marker
+    for (i, field) in opt_fields.iter().enumerate().skip(fields.len()) {
+        let field_name = fields::member(i, field);
+        let value = fields::marker_value(field);
+
+        tokens.extend(quote! {
+            #field_name: #value,
+        });
+    }
+
     tokens
 }
@@ -3,27 +3,106 @@ use quote::quote;
 use syn::ItemStruct;
 
 use crate::args::Args;
-use crate::{attrs, fields, from, merge};
+use crate::{
+    attrs, cli, debug, default, env, field_enum, field_mask, fields, from, generics, matches,
+    merge, owned, query, roles, sql, str_access, three_way, update_enum, versions, views,
+};
 
 pub fn generate(original: &ItemStruct, args: Args) -> TokenStream {
-    let mut opt_struct = original.clone();
+    if let Err(e) = fields::args::check(original) {
+        return e.to_compile_error();
+    }
+
+    if let Err(e) = fields::args::check_opt_types(original, &args) {
+        return e.to_compile_error();
+    }
+
+    // opt struct fields and generics, which only differ from the original's
+    // with the owned and unused_generics arguments; generators tied to the
+    // original's field types can't be combined with owned, so for them it only
+    // adds the unused generics marker field
+    let mut typed = owned::item(original, &args);
+    let opt_fields = fields::generate(&typed, &args);
+    generics::handle_unused(&mut typed, &opt_fields, &args);
+
+    let mut opt_struct = typed.clone();
 
     opt_struct.ident = args.item.name.clone();
     opt_struct.vis = args.item.final_visibility();
 
     opt_struct.attrs = attrs::generate(original, &args);
-    opt_struct.fields = fields::generate(original, &args);
+    opt_struct.fields = fields::generate(&typed, &args);
 
-    let merge_impl = merge::generate(original, &opt_struct, &args);
+    let merge_impl = merge::generate(&typed, &opt_struct, &args);
 
     let from_impl = from::generate(original, &opt_struct, &args);
 
+    let three_way_impl = three_way::generate(&typed, &opt_struct, &args);
+
+    let field_enum = field_enum::generate(&typed, &opt_struct, &args);
+
+    let update_enum = update_enum::generate(&typed, &opt_struct, &args);
+
+    let str_access = str_access::generate(&typed, &opt_struct, &args);
+
+    let env_impl = env::generate(&typed, &opt_struct, &args);
+
+    let cli_impl = cli::generate(&typed, &opt_struct, &args);
+
+    let query_impl = query::generate(&typed, &opt_struct, &args);
+
+    let sql_impl = sql::generate(&typed, &opt_struct, &args);
+
+    let matches_impl = matches::generate(&typed, &opt_struct, &args);
+
+    let field_mask_impl = field_mask::generate(&typed, &opt_struct, &args);
+
+    let roles_impl = roles::generate(&typed, &opt_struct, &args);
+
+    let versions_impl = versions::generate(&typed, &opt_struct, &args);
+
+    let debug_impl = debug::generate(&typed, &opt_struct, &args);
+
+    let default_impl = default::generate(&typed, &opt_struct, &args);
+
+    let views_impl = views::generate(&typed, &opt_struct, &args);
+
     quote! {
         #opt_struct
 
         #merge_impl
 
         #from_impl
+
+        #three_way_impl
+
+        #field_enum
+
+        #update_enum
+
+        #str_access
+
+        #env_impl
+
+        #cli_impl
+
+        #query_impl
+
+        #sql_impl
+
+        #matches_impl
+
+        #field_mask_impl
+
+        #roles_impl
+
+        #versions_impl
+
+        #debug_impl
+
+        #default_impl
+
+        #views_impl
     }
 }
 
@@ -110,4 +189,65 @@ mod tests {
             assert_eq!(generated.vis, vis);
         }
     }
+
+    #[test]
+    fn duplicate_flags_are_error() {
+        let (item, args) = parse_item_and_args(
+            quote! {
+                struct S {
+                    cache: bool,
+                    no_cache: bool
+                }
+            },
+            quote! {
+                Opt,
+                from_args
+            },
+        );
+
+        let generated = generate(&item, args).to_string();
+
+        assert!(generated
+            .contains("flag `--no-cache` of field `no_cache` is already used by field `cache`"));
+    }
+
+    #[test]
+    fn duplicate_long_flags_are_error() {
+        let (item, args) = parse_item_and_args(
+            quote! {
+                struct S {
+                    #[optfield(long = "name")]
+                    first: String,
+                    name: String
+                }
+            },
+            quote! {
+                Opt,
+                from_args
+            },
+        );
+
+        let generated = generate(&item, args).to_string();
+
+        assert!(
+            generated.contains("flag `--name` of field `name` is already used by field `first`")
+        );
+    }
+
+    #[test]
+    fn views_without_fields_is_error() {
+        let (item, args) = parse_item_and_args(
+            quote! {
+                struct S;
+            },
+            quote! {
+                Opt,
+                views
+            },
+        );
+
+        let generated = generate(&item, args).to_string();
+
+        assert!(generated.contains("views require at least one field"));
+    }
 }
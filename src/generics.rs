@@ -0,0 +1,480 @@
+use std::collections::HashSet;
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse_quote, punctuated::Punctuated, Field, Fields, GenericParam, Generics, ItemStruct,
+    Visibility, WherePredicate,
+};
+
+use crate::args::{Args, UnusedGenerics};
+
+const PHANTOM_FIELD: &str = "_phantom";
+
+/// Handles the generic parameters no opt field uses, as chosen by the
+/// `unused_generics` argument. With the `owned` argument they are pruned by
+/// default, since borrowed fields no longer use their lifetimes.
+pub fn handle_unused(item: &mut ItemStruct, opt_fields: &Fields, args: &Args) {
+    let mode = match (&args.unused_generics, args.owned) {
+        (Some(mode), _) => mode,
+        (None, true) => &UnusedGenerics::Prune,
+        (None, false) => return,
+    };
+
+    let types = type_tokens(opt_fields);
+
+    match mode {
+        UnusedGenerics::Prune => prune(&mut item.generics, &types),
+        UnusedGenerics::Phantom => {
+            let mut used = HashSet::new();
+            used_names(types, &mut used);
+
+            let mut markers = TokenStream::new();
+
+            for param in item.generics.params.iter() {
+                match param {
+                    GenericParam::Lifetime(param)
+                        if !used.contains(&param.lifetime.to_string()) =>
+                    {
+                        let lifetime = &param.lifetime;
+
+                        markers.extend(quote!(&#lifetime (),));
+                    }
+                    GenericParam::Type(param) if !used.contains(&param.ident.to_string()) => {
+                        let ident = &param.ident;
+
+                        markers.extend(quote!(fn() -> #ident,));
+                    }
+                    // unused const parameters are allowed
+                    _ => {}
+                }
+            }
+
+            if !markers.is_empty() {
+                add_phantom_field(&mut item.fields, markers, args.item.final_visibility());
+            }
+        }
+    }
+}
+
+/// Generics for impls involving both the original and the opt struct: the
+/// original's parameters, with the bounds the opt struct adds.
+pub fn merged(item: &Generics, opt: &Generics) -> Generics {
+    let mut generics = item.clone();
+
+    let item_predicates: HashSet<String> = item
+        .where_clause
+        .iter()
+        .flat_map(|where_clause| where_clause.predicates.iter())
+        .map(|predicate| predicate.to_token_stream().to_string())
+        .collect();
+
+    if let Some(opt_where_clause) = &opt.where_clause {
+        let added: Vec<&WherePredicate> = opt_where_clause
+            .predicates
+            .iter()
+            .filter(|p| !item_predicates.contains(&p.to_token_stream().to_string()))
+            .collect();
+
+        if !added.is_empty() {
+            generics
+                .make_where_clause()
+                .predicates
+                .extend(added.into_iter().cloned());
+        }
+    }
+
+    generics
+}
+
+/// Collects the identifiers and lifetimes found in the tokens. Lifetimes keep
+/// their leading quote.
+pub fn used_names(tokens: TokenStream, names: &mut HashSet<String>) {
+    let mut lifetime = false;
+
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) => {
+                used_names(group.stream(), names);
+                lifetime = false;
+            }
+            TokenTree::Punct(punct) => lifetime = punct.as_char() == '\'',
+            TokenTree::Ident(ident) => {
+                if lifetime {
+                    names.insert(format!("'{}", ident));
+                } else {
+                    names.insert(ident.to_string());
+                }
+
+                lifetime = false;
+            }
+            TokenTree::Literal(_) => lifetime = false,
+        }
+    }
+}
+
+/// Whether the tokens mention any of the names.
+pub fn mentions<T: ToTokens>(tokens: &T, names: &HashSet<String>) -> bool {
+    let mut used = HashSet::new();
+    used_names(tokens.to_token_stream(), &mut used);
+
+    used.iter().any(|name| names.contains(name))
+}
+
+/// Removes the lifetime, type and const parameters not mentioned in the
+/// tokens, along with the bounds and where predicates mentioning them.
+pub fn prune(generics: &mut Generics, tokens: &TokenStream) {
+    let mut used = HashSet::new();
+    used_names(tokens.clone(), &mut used);
+
+    let removed: HashSet<String> = generics
+        .params
+        .iter()
+        .map(param_name)
+        .filter(|name| !used.contains(name))
+        .collect();
+
+    remove_params(generics, &removed);
+}
+
+/// Removes the named parameters, dropping the bounds and where predicates
+/// that mention them.
+fn remove_params(generics: &mut Generics, removed: &HashSet<String>) {
+    if removed.is_empty() {
+        return;
+    }
+
+    let params = std::mem::take(&mut generics.params);
+
+    generics.params = params
+        .into_iter()
+        .filter(|param| !removed.contains(&param_name(param)))
+        .map(|mut param| {
+            match &mut param {
+                GenericParam::Lifetime(param) => {
+                    param.bounds = retain(&param.bounds, |b| !mentions(b, removed));
+
+                    if param.bounds.is_empty() {
+                        param.colon_token = None;
+                    }
+                }
+                GenericParam::Type(param) => {
+                    param.bounds = retain(&param.bounds, |b| !mentions(b, removed));
+
+                    if param.bounds.is_empty() {
+                        param.colon_token = None;
+                    }
+                }
+                GenericParam::Const(_) => {}
+            }
+
+            param
+        })
+        .collect();
+
+    if let Some(where_clause) = &mut generics.where_clause {
+        let predicates = std::mem::take(&mut where_clause.predicates);
+
+        where_clause.predicates = predicates
+            .into_iter()
+            .filter_map(|mut predicate| {
+                match &mut predicate {
+                    WherePredicate::Lifetime(predicate) => {
+                        if mentions(&predicate.lifetime, removed) {
+                            return None;
+                        }
+
+                        predicate.bounds = retain(&predicate.bounds, |b| !mentions(b, removed));
+
+                        if predicate.bounds.is_empty() {
+                            return None;
+                        }
+                    }
+                    WherePredicate::Type(predicate) => {
+                        if mentions(&predicate.bounded_ty, removed) {
+                            return None;
+                        }
+
+                        predicate.bounds = retain(&predicate.bounds, |b| !mentions(b, removed));
+
+                        if predicate.bounds.is_empty() {
+                            return None;
+                        }
+                    }
+                    _ => {}
+                }
+
+                Some(predicate)
+            })
+            .collect();
+
+        if where_clause.predicates.is_empty() {
+            generics.where_clause = None;
+        }
+    }
+}
+
+fn type_tokens(fields: &Fields) -> TokenStream {
+    let mut tokens = TokenStream::new();
+
+    for field in fields.iter() {
+        field.ty.to_tokens(&mut tokens);
+    }
+
+    tokens
+}
+
+/// Appends a `PhantomData` field holding the markers, which is left out of
+/// generated methods like any other marker field. It has the visibility of the
+/// opt struct, so that it can be built where the struct is visible.
+fn add_phantom_field(fields: &mut Fields, markers: TokenStream, vis: Visibility) {
+    let ty = parse_quote!(::std::marker::PhantomData<(#markers)>);
+
+    let field = |ident| Field {
+        attrs: Vec::new(),
+        vis,
+        mutability: syn::FieldMutability::None,
+        ident,
+        colon_token: None,
+        ty,
+    };
+
+    match fields {
+        Fields::Named(named) => {
+            let mut phantom = field(Some(format_ident!("{}", PHANTOM_FIELD)));
+            phantom.colon_token = Some(Default::default());
+
+            named.named.push(phantom);
+        }
+        Fields::Unnamed(unnamed) => unnamed.unnamed.push(field(None)),
+        Fields::Unit => {}
+    }
+}
+
+fn param_name(param: &GenericParam) -> String {
+    match param {
+        GenericParam::Lifetime(param) => param.lifetime.to_string(),
+        GenericParam::Type(param) => param.ident.to_string(),
+        GenericParam::Const(param) => param.ident.to_string(),
+    }
+}
+
+fn retain<T: Clone, P: Default>(
+    punctuated: &Punctuated<T, P>,
+    keep: impl Fn(&T) -> bool,
+) -> Punctuated<T, P> {
+    punctuated.iter().filter(|t| keep(t)).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use quote::quote;
+
+    use crate::fields;
+    use crate::test_util::*;
+
+    #[test]
+    fn prunes_unused_lifetimes() {
+        let mut item = parse_item(quote! {
+            struct S<'a, 'b: 'a, 'c, T: 'a + Clone>
+            where
+                T: 'b,
+                'c: 'a,
+            {
+                field: &'c T,
+                other: String
+            }
+        });
+
+        prune(&mut item.generics, &type_tokens(&item.fields));
+
+        let expected = parse_item(quote! {
+            struct S<'c, T: Clone> {
+                field: &'c T,
+                other: String
+            }
+        });
+
+        assert_eq!(item.generics, expected.generics);
+    }
+
+    #[test]
+    fn prunes_unused_params() {
+        let mut item = parse_item(quote! {
+            struct S<'a, T: Into<U>, U, const N: usize>
+            where
+                U: Clone,
+            {
+                field: &'a T
+            }
+        });
+
+        prune(&mut item.generics, &quote!(#[cfg(test)] Field(&'a T),));
+
+        let expected = parse_item(quote! {
+            struct S<'a, T> {
+                field: &'a T
+            }
+        });
+
+        assert_eq!(item.generics, expected.generics);
+    }
+
+    #[test]
+    fn phantom_for_unused_params() {
+        let (mut item, args) = parse_item_and_args(
+            quote! {
+                struct S<'a, 'b, T, U, const N: usize> {
+                    field: &'a T
+                }
+            },
+            quote! {
+                Opt,
+                unused_generics = phantom
+            },
+        );
+
+        let expected = parse_item(quote! {
+            struct S<'a, 'b, T, U, const N: usize> {
+                field: &'a T,
+                _phantom: ::std::marker::PhantomData<(&'b (), fn() -> U,)>
+            }
+        });
+
+        let opt_fields = fields::generate(&item, &args);
+        handle_unused(&mut item, &opt_fields, &args);
+
+        assert_eq!(item, expected);
+    }
+
+    #[test]
+    fn phantom_for_unused_params_tuple_struct() {
+        let (mut item, args) = parse_item_and_args(
+            quote! {
+                struct S<T>(u8);
+            },
+            quote! {
+                Opt,
+                unused_generics = phantom
+            },
+        );
+
+        let expected = parse_item(quote! {
+            struct S<T>(u8, ::std::marker::PhantomData<(fn() -> T,)>);
+        });
+
+        let opt_fields = fields::generate(&item, &args);
+        handle_unused(&mut item, &opt_fields, &args);
+
+        assert_eq!(item.fields, expected.fields);
+    }
+
+    #[test]
+    fn phantom_for_params_only_used_by_original() {
+        let (mut item, args) = parse_item_and_args(
+            quote! {
+                struct S<T> {
+                    #[optfield(nested = Nested)]
+                    field: T
+                }
+            },
+            quote! {
+                pub(crate) Opt,
+                field_mask,
+                unused_generics = phantom
+            },
+        );
+
+        let expected = parse_item(quote! {
+            struct S<T> {
+                field: T,
+                pub(crate) _phantom: ::std::marker::PhantomData<(fn() -> T,)>
+            }
+        });
+
+        let opt_fields = fields::generate(&item, &args);
+        handle_unused(&mut item, &opt_fields, &args);
+
+        let phantom = item.fields.iter().last().unwrap();
+        let expected_phantom = expected.fields.iter().last().unwrap();
+
+        assert_eq!(phantom.vis, expected_phantom.vis);
+        assert_eq!(phantom.ty, expected_phantom.ty);
+    }
+
+    #[test]
+    fn no_phantom_without_unused_params() {
+        let (mut item, args) = parse_item_and_args(
+            quote! {
+                struct S<'a, T> {
+                    field: &'a T
+                }
+            },
+            quote! {
+                Opt,
+                unused_generics = phantom
+            },
+        );
+
+        let expected = item.clone();
+
+        let opt_fields = fields::generate(&item, &args);
+        handle_unused(&mut item, &opt_fields, &args);
+
+        assert_eq!(item, expected);
+    }
+
+    #[test]
+    fn merged_adds_opt_predicates() {
+        let item = parse_item(quote! {
+            struct S<'a, T>
+            where
+                T: Clone
+            {
+                field: &'a T
+            }
+        });
+
+        let opt = parse_item(quote! {
+            struct Opt<T>
+            where
+                T: Clone,
+                T: ToOwned
+            {
+                field: Option<T::Owned>
+            }
+        });
+
+        let expected = parse_item(quote! {
+            struct S<'a, T>
+            where
+                T: Clone,
+                T: ToOwned
+            {
+                field: &'a T
+            }
+        });
+
+        assert_eq!(merged(&item.generics, &opt.generics), expected.generics);
+    }
+
+    #[test]
+    fn keeps_used_lifetimes() {
+        let mut item = parse_item(quote! {
+            struct S<'a, T>
+            where
+                T: 'a,
+            {
+                field: &'a T
+            }
+        });
+
+        let expected = item.generics.clone();
+
+        prune(&mut item.generics, &type_tokens(&item.fields));
+
+        assert_eq!(item.generics, expected);
+    }
+}
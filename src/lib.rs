@@ -7,12 +7,34 @@
 //! * [Simple examples](#simple-examples)
 //! * [Visibility](#visibility)
 //! * [Rewrapping `Option` fields](#rewrapping-option-fields)
+//! * [Marker fields](#marker-fields)
 //! * [Documentation](#documentation)
 //! * [Attributes](#attributes)
 //! * [Field documentation](#field-documentation)
 //! * [Field attributes](#field-attributes)
 //! * [Merging](#merging)
 //! * [From](#from)
+//! * [Three-way merge](#three-way-merge)
+//! * [Filling](#filling)
+//! * [Validation](#validation)
+//! * [Readonly fields](#readonly-fields)
+//! * [Conditional merging](#conditional-merging)
+//! * [Field enum](#field-enum)
+//! * [Update enum](#update-enum)
+//! * [String access](#string-access)
+//! * [Environment variables](#environment-variables)
+//! * [Command-line arguments](#command-line-arguments)
+//! * [Query strings](#query-strings)
+//! * [SQL updates](#sql-updates)
+//! * [Matching](#matching)
+//! * [Field masks](#field-masks)
+//! * [Roles](#roles)
+//! * [Versions](#versions)
+//! * [Debug](#debug)
+//! * [Default](#default)
+//! * [Views](#views)
+//! * [Owned](#owned)
+//! * [Unused generics](#unused-generics)
 //!
 //! # Simple examples
 //! The first argument is the name of the generated struct:
@@ -109,6 +131,28 @@
 //! }
 //! ```
 //!
+//! # Marker fields
+//! `PhantomData` and `()` fields hold no data, so they are never wrapped:
+//! ```
+//! # use optfield::*;
+//! # use std::marker::PhantomData;
+//! #[optfield(Opt)]
+//! struct Id<T> {
+//!     value: u64,
+//!     marker: PhantomData<T>
+//! }
+//! ```
+//! Will generate:
+//! ```
+//! # use std::marker::PhantomData;
+//! struct Opt<T> {
+//!     value: Option<u64>,
+//!     marker: PhantomData<T>
+//! }
+//! ```
+//! Generated methods leave them out, and fill them in when building opt
+//! structs.
+//!
 //! # Documentation
 //! To document the opt struct, either duplicate the same documentation as the
 //! original using the `doc` argument by itself:
@@ -428,6 +472,68 @@
 //! * custom visibility (default is private): `merge_fn = pub(crate)`
 //! * both: `merge_fn = pub my_merge_fn`
 //!
+//! Two more variants of the merge method can be added, both accepting the same
+//! name and visibility customization:
+//! * `merge_ref_fn` adds `fn merge_opt_ref(&mut self, opt: &Opt)`, which clones
+//!   the values that are `Some(...)`; field value types must implement `Clone`
+//! * `with_fn` adds `fn with_opt(self, opt: Opt) -> Self`, which consumes the
+//!   original and returns it merged
+//! * `merge_arc_fn` adds `fn merge_opt_arc(this: &mut Arc<Self>, opt: Opt)`,
+//!   which only calls [`Arc::make_mut`] if at least one field value changes;
+//!   the original must implement `Clone` and field value types `PartialEq`
+//!
+//! [`Arc::make_mut`]: std::sync::Arc::make_mut
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, merge_ref_fn, with_fn)]
+//! struct MyStruct {
+//!     text: String,
+//!     number: i32
+//! }
+//!
+//! let opt = Opt {
+//!     text: Some("amazing".to_string()),
+//!     number: None
+//! };
+//!
+//! let mut original = MyStruct {
+//!     text: "awesome".to_string(),
+//!     number: 1
+//! };
+//!
+//! original.merge_opt_ref(&opt);
+//! assert_eq!(original.text, "amazing");
+//!
+//! let original = original.with_opt(Opt {
+//!     text: None,
+//!     number: Some(2)
+//! });
+//! assert_eq!(original.text, "amazing");
+//! assert_eq!(original.number, 2);
+//! ```
+//! Readers holding a clone of an `Arc` keep their snapshot:
+//! ```
+//! # use optfield::*;
+//! use std::sync::Arc;
+//!
+//! #[optfield(Opt, merge_arc_fn)]
+//! #[derive(Clone)]
+//! struct MyStruct {
+//!     number: i32
+//! }
+//!
+//! let mut shared = Arc::new(MyStruct { number: 1 });
+//! let snapshot = Arc::clone(&shared);
+//!
+//! // nothing changes, so nothing is cloned
+//! MyStruct::merge_opt_arc(&mut shared, Opt { number: Some(1) });
+//! assert!(Arc::ptr_eq(&shared, &snapshot));
+//!
+//! MyStruct::merge_opt_arc(&mut shared, Opt { number: Some(2) });
+//! assert_eq!(shared.number, 2);
+//! assert_eq!(snapshot.number, 1);
+//! ```
+//!
 //! # From
 //! When the `from` argument is used, `From<MyStruct>` is implemented for `Opt`.
 //! ```
@@ -447,6 +553,892 @@
 //! assert_eq!(from.text.unwrap(), "super");
 //! assert_eq!(from.number.unwrap(), 2);
 //! ```
+//! `from = ref` implements `From<&MyStruct>` instead, cloning the fields, and
+//! `from = (owned, ref)` implements both:
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, from = (owned, ref))]
+//! struct MyStruct {
+//!     text: String,
+//! }
+//!
+//! let original = MyStruct {
+//!     text: "super".to_string(),
+//! };
+//!
+//! let from_ref = Opt::from(&original);
+//! assert_eq!(from_ref.text.unwrap(), "super");
+//!
+//! let from = Opt::from(original);
+//! assert_eq!(from.text.unwrap(), "super");
+//! ```
+//!
+//! # Three-way merge
+//! The `three_way` argument adds an associated function to the opt struct that
+//! merges two opt structs created from the same original, called base:
+//! ```
+//! # struct MyStruct;
+//! # struct Opt;
+//! # struct OptConflicts;
+//! # impl Opt {
+//! fn three_way(base: &MyStruct, ours: Opt, theirs: Opt) -> Result<Opt, OptConflicts>
+//! # { Ok(ours) }
+//! # }
+//! ```
+//! Fields set by only one side are taken from that side. Fields set by both
+//! sides are taken if the values are equal or if one of them is the same as the
+//! base value. Otherwise the field is in conflict and an error is returned.
+//!
+//! The error type is named after the opt struct with a `Conflicts` suffix. It
+//! has the same fields as the opt struct, holding both values of each conflict.
+//! Field value types must implement `PartialEq`.
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, three_way)]
+//! struct MyStruct {
+//!     text: String,
+//!     number: i32,
+//! }
+//!
+//! let base = MyStruct {
+//!     text: "base".to_string(),
+//!     number: 1,
+//! };
+//!
+//! let ours = Opt {
+//!     text: Some("ours".to_string()),
+//!     number: Some(2),
+//! };
+//!
+//! let theirs = Opt {
+//!     text: Some("base".to_string()),
+//!     number: Some(3),
+//! };
+//!
+//! let conflicts = match Opt::three_way(&base, ours, theirs) {
+//!     Ok(_) => unreachable!(),
+//!     Err(conflicts) => conflicts,
+//! };
+//!
+//! assert_eq!(conflicts.fields(), vec!["number"]);
+//! assert_eq!(conflicts.number, Some((2, 3)));
+//! // theirs did not change text, so there is no conflict
+//! assert_eq!(conflicts.text, None);
+//! ```
+//! Like `merge_fn`, the function can be given a custom name and visibility:
+//! `three_way = pub(crate) my_three_way_fn`.
+//!
+//! # Filling
+//! The `fill_fn` argument adds a method to the original struct that, unlike the
+//! merge method, only sets fields that are considered unset. It is named
+//! `fill_opt` by default and takes the opt struct by value.
+//!
+//! `Option` fields are unset when they are `None`. Other fields are never
+//! overwritten, unless they have the `#[optfield(fill_default)]` field
+//! argument, in which case they are unset when equal to their default value.
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, fill_fn)]
+//! struct MyStruct {
+//!     text: Option<String>,
+//!     number: i32,
+//!     #[optfield(fill_default)]
+//!     counter: u32,
+//! }
+//!
+//! let mut original = MyStruct {
+//!     text: None,
+//!     number: 1,
+//!     counter: 0,
+//! };
+//!
+//! let opt = Opt {
+//!     text: Some("fallback".to_string()),
+//!     number: Some(2),
+//!     counter: Some(3),
+//! };
+//!
+//! original.fill_opt(opt);
+//!
+//! assert_eq!(original.text.unwrap(), "fallback");
+//! // number is always considered set
+//! assert_eq!(original.number, 1);
+//! assert_eq!(original.counter, 3);
+//! ```
+//! The fill method can be given a custom name and visibility like `merge_fn`.
+//!
+//! `#[optfield(...)]` field attributes are removed from the original struct
+//! once the last `optfield` attribute is expanded.
+//!
+//! # Validation
+//! The `try_merge_fn` argument adds a merge method that validates the values
+//! before keeping them:
+//! ```
+//! # struct Opt;
+//! # struct MyError;
+//! # impl Opt {
+//! // validate_error = MyError
+//! fn try_merge_opt(&mut self, opt: Opt) -> Result<(), MyError>
+//! # { Ok(()) }
+//! # }
+//! ```
+//! The error type is given using the required `validate_error` argument.
+//!
+//! Field validators are given using the `#[optfield(validate = ...)]` field
+//! argument. They receive a reference to the opt value and run before any field
+//! is set.
+//!
+//! A validator for the whole struct is given using the `validate` argument. It
+//! receives a reference to the original after all values are set. If it fails,
+//! the previous values are set back, so the original is left untouched.
+//!
+//! Validator errors only need to be convertible into the error type.
+//! ```
+//! # use optfield::*;
+//! #[derive(Debug, PartialEq)]
+//! enum MyError {
+//!     Empty,
+//!     MinAboveMax,
+//! }
+//!
+//! fn not_empty(text: &String) -> Result<(), MyError> {
+//!     if text.is_empty() {
+//!         Err(MyError::Empty)
+//!     } else {
+//!         Ok(())
+//!     }
+//! }
+//!
+//! fn check_range(original: &MyStruct) -> Result<(), MyError> {
+//!     if original.min > original.max {
+//!         Err(MyError::MinAboveMax)
+//!     } else {
+//!         Ok(())
+//!     }
+//! }
+//!
+//! #[optfield(Opt, try_merge_fn, validate = check_range, validate_error = MyError)]
+//! struct MyStruct {
+//!     #[optfield(validate = not_empty)]
+//!     name: String,
+//!     min: i32,
+//!     max: i32,
+//! }
+//!
+//! let mut original = MyStruct {
+//!     name: "range".to_string(),
+//!     min: 1,
+//!     max: 10,
+//! };
+//!
+//! let opt = Opt {
+//!     name: None,
+//!     min: Some(5),
+//!     max: Some(2),
+//! };
+//!
+//! assert_eq!(original.try_merge_opt(opt), Err(MyError::MinAboveMax));
+//! // nothing was changed
+//! assert_eq!(original.min, 1);
+//! assert_eq!(original.max, 10);
+//! ```
+//! The method can be given a custom name and visibility like `merge_fn`.
+//!
+//! # Readonly fields
+//! Fields with the `#[optfield(readonly)]` field argument are part of the opt
+//! struct, and set by `from`, but are never set by merge methods.
+//!
+//! With `#[optfield(readonly = reject)]` the method generated by `merge_fn`
+//! returns `Result<(), &'static str>` instead, and fails with the field name
+//! if a readonly opt value is `Some(...)`, before setting any field. The method
+//! generated by `try_merge_fn` rejects them the same way, converting the field
+//! name into the `validate_error` type, which must then implement
+//! `From<&'static str>`. Other merge methods ignore readonly fields either way.
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, merge_fn)]
+//! struct MyStruct {
+//!     #[optfield(readonly = reject)]
+//!     id: u32,
+//!     text: String,
+//! }
+//!
+//! let mut original = MyStruct {
+//!     id: 1,
+//!     text: "awesome".to_string(),
+//! };
+//!
+//! let opt = Opt {
+//!     id: Some(2),
+//!     text: Some("amazing".to_string()),
+//! };
+//!
+//! assert_eq!(original.merge_opt(opt), Err("id"));
+//! assert_eq!(original.text, "awesome");
+//! ```
+//!
+//! # Conditional merging
+//! The `#[optfield(merge_if = ...)]` field argument takes a predicate that
+//! decides whether merge methods set the field. It receives references to the
+//! current and the new value, both of the original field type.
+//! ```
+//! # use optfield::*;
+//! fn newer(current: &u32, new: &u32) -> bool {
+//!     new > current
+//! }
+//!
+//! #[optfield(Opt, merge_fn)]
+//! struct MyStruct {
+//!     #[optfield(merge_if = newer)]
+//!     version: u32,
+//! }
+//!
+//! let mut original = MyStruct { version: 2 };
+//!
+//! original.merge_opt(Opt { version: Some(1) });
+//! assert_eq!(original.version, 2);
+//!
+//! original.merge_opt(Opt { version: Some(3) });
+//! assert_eq!(original.version, 3);
+//! ```
+//!
+//! # Field enum
+//! The `field_enum` argument generates an enum named after the opt struct
+//! with a `Field` suffix, with one variant per field, and methods on the opt
+//! struct to check which fields are set:
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, field_enum)]
+//! struct MyStruct {
+//!     text: String,
+//!     number: i32,
+//! }
+//!
+//! let opt = Opt {
+//!     text: None,
+//!     number: Some(1),
+//! };
+//!
+//! assert_eq!(OptField::FIELDS, &[OptField::Text, OptField::Number]);
+//! assert_eq!(OptField::Number.name(), "number");
+//!
+//! assert!(opt.is_set(OptField::Number));
+//! assert_eq!(opt.set_fields().collect::<Vec<_>>(), vec![OptField::Number]);
+//! assert_eq!(opt.len(), 1);
+//! assert!(!opt.is_empty());
+//! assert!(!opt.is_complete());
+//! ```
+//! Variants are named after the fields in CamelCase, tuple struct fields use
+//! `_0`, `_1` and so on. `name()` returns the original field name, or the
+//! variant name for tuple struct fields. Variants that would be empty or start
+//! with a digit, for fields like `__` or `_1`, get a `Field` prefix. Fields that
+//! would get the same variant, like `foo_1` and `foo1`, are an error.
+//!
+//! # Update enum
+//! The `update_enum` argument generates an enum named after the original
+//! struct with an `Update` suffix, with one variant per field holding its new
+//! value, along with conversions from and to the opt struct and a method to
+//! apply a single update to the original struct:
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, update_enum = (derive(Debug, PartialEq)))]
+//! struct MyStruct {
+//!     text: String,
+//!     number: i32,
+//! }
+//!
+//! let opt = Opt {
+//!     text: Some("new".to_string()),
+//!     number: None,
+//! };
+//!
+//! let updates = opt.into_updates();
+//! assert_eq!(updates, vec![MyStructUpdate::Text("new".to_string())]);
+//!
+//! let mut original = MyStruct {
+//!     text: "old".to_string(),
+//!     number: 1,
+//! };
+//!
+//! for update in updates {
+//!     original.apply_update(update);
+//! }
+//!
+//! assert_eq!(original.text, "new");
+//!
+//! let opt: Opt = vec![MyStructUpdate::Number(2)].into_iter().collect();
+//! assert_eq!(opt.number, Some(2));
+//! ```
+//! Attributes for the enum can be given in parentheses, like with `attrs`.
+//! Variants are named like those of the field enum, and hold the same value
+//! type as merge methods, so `Option` fields that are not rewrapped hold their
+//! inner type. When collecting into the opt struct, the last update of a field
+//! wins. Like merge methods, `apply_update` leaves readonly fields untouched
+//! and only sets fields whose `merge_if` predicate holds.
+//!
+//! # String access
+//! The `str_access` argument generates methods to set fields by name from a
+//! string, parsed with [`FromStr`](std::str::FromStr), and to get the `Debug`
+//! representation of set fields by name. Additional names can be given with
+//! the `#[optfield(alias = "...")]` field argument, which can be repeated:
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, str_access)]
+//! struct MyStruct {
+//!     text: String,
+//!     #[optfield(alias = "max-conns")]
+//!     max_conns: u32,
+//! }
+//!
+//! let mut opt = Opt {
+//!     text: None,
+//!     max_conns: None,
+//! };
+//!
+//! opt.set_str("max-conns", "10").unwrap();
+//! assert_eq!(opt.max_conns, Some(10));
+//!
+//! assert_eq!(opt.get_debug("max_conns"), Some("10".to_string()));
+//! assert_eq!(opt.get_debug("text"), None);
+//!
+//! assert_eq!(
+//!     opt.set_str("max_conns", "many"),
+//!     Err(OptSetError::InvalidValue {
+//!         key: "max_conns",
+//!         message: "invalid digit found in string".to_string(),
+//!     })
+//! );
+//! assert_eq!(
+//!     opt.set_str("unknown", "1"),
+//!     Err(OptSetError::UnknownKey("unknown".to_string()))
+//! );
+//! ```
+//! The error type is named after the opt struct with a `SetError` suffix.
+//! Tuple struct fields are accessed by index. Rewrapped `Option` fields parse
+//! the inner type and are set to `Some(Some(value))`.
+//!
+//! # Environment variables
+//! The `from_env` argument generates constructors reading fields from
+//! environment variables, parsed with [`FromStr`](std::str::FromStr). Each
+//! field is read from `PREFIX_FIELD_NAME`, or from `FIELD_NAME` if the prefix
+//! is empty, and the `#[optfield(env = "...")]` field argument replaces the
+//! whole variable name. Unset variables leave the field as `None`:
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, from_env)]
+//! struct MyStruct {
+//!     max_conns: u32,
+//!     #[optfield(env = "DATABASE_URL")]
+//!     database_url: String,
+//!     timeout: u64,
+//! }
+//!
+//! std::env::set_var("APP_MAX_CONNS", "10");
+//! std::env::set_var("DATABASE_URL", "postgres://localhost");
+//!
+//! let opt = Opt::from_env("APP").unwrap();
+//!
+//! assert_eq!(opt.max_conns, Some(10));
+//! assert_eq!(opt.database_url, Some("postgres://localhost".to_string()));
+//! assert_eq!(opt.timeout, None);
+//!
+//! // variables can also be given directly, e.g. for tests
+//! let vars = vec![("APP_TIMEOUT".to_string(), "never".to_string())];
+//!
+//! match Opt::from_env_iter("APP", vars) {
+//!     Err(OptEnvError::InvalidValue { var, .. }) => assert_eq!(var, "APP_TIMEOUT"),
+//!     _ => panic!("expected invalid value"),
+//! }
+//! ```
+//! The error type is named after the opt struct with an `EnvError` suffix.
+//! Tuple struct fields use their index as name. Rewrapped `Option` fields
+//! parse the inner type and are set to `Some(Some(value))`.
+//!
+//! # Command-line arguments
+//! The `from_args` argument generates a constructor reading fields from
+//! command-line style arguments, parsed with [`FromStr`](std::str::FromStr).
+//! Fields are set with `--field-name value` or `--field-name=value`, and `bool`
+//! fields with `--field-name` and `--no-field-name`. Flag names are the field
+//! names in kebab-case, and can be replaced with the `#[optfield(long = "...")]`
+//! field argument. Fields sharing a flag, like `cache: bool` and `no_cache`,
+//! are an error. Arguments that don't match any field are returned along with
+//! the opt struct, and so is everything after `--`:
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, from_args)]
+//! struct MyStruct {
+//!     max_conns: u32,
+//!     #[optfield(long = "db")]
+//!     database_url: String,
+//!     verbose: bool,
+//! }
+//!
+//! let args = vec!["--max-conns=10", "input.txt", "--db", "postgres://localhost", "--no-verbose"];
+//!
+//! let (opt, rest) = Opt::from_args(args.into_iter().map(String::from)).unwrap();
+//!
+//! assert_eq!(opt.max_conns, Some(10));
+//! assert_eq!(opt.database_url, Some("postgres://localhost".to_string()));
+//! assert_eq!(opt.verbose, Some(false));
+//! assert_eq!(rest, vec!["input.txt"]);
+//! ```
+//! The program name is not skipped, so pass `std::env::args().skip(1)` to read
+//! the actual command-line arguments. The error type is named after the opt
+//! struct with an `ArgsError` suffix.
+//!
+//! # Query strings
+//! The `query` argument generates methods to parse an opt struct from a URL
+//! query string or form-urlencoded body, and to format it back. Keys are the
+//! field names and values are percent-decoded and encoded, and converted with
+//! [`FromStr`](std::str::FromStr) and [`Display`](std::fmt::Display):
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, query)]
+//! struct MyStruct {
+//!     name: String,
+//!     limit: u32,
+//!     offset: u32,
+//! }
+//!
+//! let opt = Opt::from_query("name=caf%C3%A9+bar&limit=10").unwrap();
+//!
+//! assert_eq!(opt.name, Some("café bar".to_string()));
+//! assert_eq!(opt.limit, Some(10));
+//! assert_eq!(opt.offset, None);
+//!
+//! assert_eq!(opt.to_query(), "name=caf%C3%A9+bar&limit=10");
+//! ```
+//! Only set fields are formatted, and a leading `?` is ignored when parsing.
+//! Unknown keys are ignored, while repeated keys are an error. The error type
+//! is named after the opt struct with a `QueryError` suffix. Rewrapped
+//! `Option` fields set to `Some(None)` are left out of the query string, since
+//! it can't express them.
+//!
+//! # SQL updates
+//! The `sql` argument generates a method building an `UPDATE` statement that
+//! sets the columns of the set fields, along with their values. It returns
+//! `None` when no field is set. Values are returned as `&dyn ToSqlValue`, a
+//! trait that must be in scope where the macro is used, so it can be adapted
+//! to any database library:
+//! ```
+//! # use optfield::*;
+//! #[derive(Debug, PartialEq)]
+//! enum SqlValue {
+//!     Text(String),
+//!     Integer(i64),
+//! }
+//!
+//! trait ToSqlValue {
+//!     fn to_sql(&self) -> SqlValue;
+//! }
+//!
+//! impl ToSqlValue for String {
+//!     fn to_sql(&self) -> SqlValue {
+//!         SqlValue::Text(self.clone())
+//!     }
+//! }
+//!
+//! impl ToSqlValue for i32 {
+//!     fn to_sql(&self) -> SqlValue {
+//!         SqlValue::Integer(i64::from(*self))
+//!     }
+//! }
+//!
+//! #[optfield(Opt, sql)]
+//! struct MyStruct {
+//!     name: String,
+//!     #[optfield(column = "user_age")]
+//!     age: i32,
+//!     email: String,
+//! }
+//!
+//! let opt = Opt {
+//!     name: Some("test".to_string()),
+//!     age: Some(30),
+//!     email: None,
+//! };
+//!
+//! let (sql, values) = opt.to_update("users").unwrap();
+//!
+//! assert_eq!(sql, "UPDATE users SET name = ?, user_age = ?");
+//! assert_eq!(values[0].to_sql(), SqlValue::Text("test".to_string()));
+//! assert_eq!(values[1].to_sql(), SqlValue::Integer(30));
+//! ```
+//! Values are meant to be bound to the placeholders by the database library.
+//! Formatting them into the statement instead would open the door to SQL
+//! injection. Columns are named after the fields unless given with the
+//! `#[optfield(column = "...")]` field argument. Placeholders are `?` by
+//! default, `sql = numbered` uses `$1`, `$2` and so on instead. Table and column
+//! names are inserted as is, without quoting, so they must not come from user
+//! input.
+//!
+//! # Matching
+//! The `matches` argument generates methods to use the opt struct as a filter:
+//! an original struct matches when all set fields are equal to the original
+//! ones. Fields can be compared differently with the
+//! `#[optfield(match = ...)]` field argument:
+//! * `contains` and `prefix` for types implementing `AsRef<str>`.
+//! * `min` and `max` for types implementing `PartialOrd`, using the opt value
+//!   as an inclusive lower or upper bound.
+//! * `range` for types implementing `PartialOrd`. The opt field then holds a
+//!   `RangeInclusive` of the value type, containing the matching values.
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, matches)]
+//! struct MyStruct {
+//!     #[optfield(match = prefix)]
+//!     name: String,
+//!     #[optfield(match = min)]
+//!     age: u32,
+//!     active: bool,
+//! }
+//!
+//! let items = vec![
+//!     MyStruct { name: "first".to_string(), age: 30, active: true },
+//!     MyStruct { name: "second".to_string(), age: 40, active: true },
+//!     MyStruct { name: "fourth".to_string(), age: 50, active: false },
+//! ];
+//!
+//! let opt = Opt {
+//!     name: Some("f".to_string()),
+//!     age: None,
+//!     active: Some(true),
+//! };
+//!
+//! assert!(opt.matches(&items[0]));
+//!
+//! let opt = Opt {
+//!     name: None,
+//!     age: Some(35),
+//!     active: None,
+//! };
+//!
+//! let names: Vec<_> = opt.filter(&items).map(|item| item.name.as_str()).collect();
+//! assert_eq!(names, vec!["second", "fourth"]);
+//! ```
+//! `Option` fields that are not rewrapped only match original fields set to a
+//! matching value.
+//!
+//! Since `range` fields don't hold the original field type, an attribute with
+//! `matches` and `range` fields can only also use `default`, `roles` and
+//! `versions`. The other `optfield` attributes of the struct hold plain values:
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, matches)]
+//! struct MyStruct {
+//!     #[optfield(match = range)]
+//!     age: u32,
+//! }
+//!
+//! let opt = Opt { age: Some(18..=30) };
+//!
+//! assert!(opt.matches(&MyStruct { age: 20 }));
+//! assert!(!opt.matches(&MyStruct { age: 40 }));
+//! ```
+//!
+//! # Field masks
+//! The `field_mask` argument generates methods to convert between the opt
+//! struct and a field mask, the list of field paths to update along with a
+//! full original struct, as used by protobuf APIs:
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, field_mask)]
+//! struct MyStruct {
+//!     name: String,
+//!     age: u32,
+//! }
+//!
+//! let source = MyStruct {
+//!     name: "test".to_string(),
+//!     age: 30,
+//! };
+//!
+//! let opt = Opt::from_field_mask(&["age"], &source).unwrap();
+//!
+//! assert_eq!(opt.name, None);
+//! assert_eq!(opt.age, Some(30));
+//! assert_eq!(opt.field_mask(), vec!["age"]);
+//!
+//! assert!(matches!(
+//!     Opt::from_field_mask(&["unknown"], &source),
+//!     Err(OptMaskError::UnknownField(_))
+//! ));
+//! ```
+//! Paths are the field names, or indexes for tuple structs. Since opt fields
+//! hold whole values, nested paths like `name.first` can't be represented and
+//! are an error, unless the field is nested (see below). `Option` fields that are not rewrapped stay `None` when the
+//! source field is `None`, so they are left out of the mask again. The error
+//! type is named after the opt struct with a `MaskError` suffix.
+//!
+//! The `#[optfield(nested = ...)]` field argument takes the opt struct of the
+//! field type, which must also use `field_mask`. The opt field then holds that
+//! opt struct, and paths below the field like `address.city` are resolved by
+//! it. The field path alone sets the whole field with `From`:
+//! ```
+//! # use optfield::*;
+//! #[optfield(AddressOpt, from, field_mask)]
+//! #[derive(Clone)]
+//! struct Address {
+//!     city: String,
+//!     street: String,
+//! }
+//!
+//! #[optfield(Opt, from, field_mask)]
+//! struct MyStruct {
+//!     name: String,
+//!     #[optfield(nested = AddressOpt)]
+//!     address: Address,
+//! }
+//!
+//! let source = MyStruct {
+//!     name: "test".to_string(),
+//!     address: Address {
+//!         city: "city".to_string(),
+//!         street: "street".to_string(),
+//!     },
+//! };
+//!
+//! let opt = Opt::from_field_mask(&["address.city"], &source).unwrap();
+//!
+//! let address = opt.address.as_ref().unwrap();
+//! assert_eq!(address.city, Some("city".to_string()));
+//! assert_eq!(address.street, None);
+//! assert_eq!(opt.field_mask(), vec!["address.city"]);
+//! ```
+//! Fields are only nested in attributes with `field_mask`. Since nested opt
+//! fields don't hold the original field type, these attributes can only also
+//! use `from`, `default`, `roles` and `versions`. The other `optfield`
+//! attributes of the struct hold plain values.
+//!
+//! # Roles
+//! The `roles` argument takes an enum type, and the
+//! `#[optfield(roles = (...))]` field argument lists the variants of the roles
+//! allowed to set a field. Fields without it can be set by any role. Methods
+//! are generated to clear the fields a role may not set, returning their
+//! names, or to check that none is set:
+//! ```
+//! # use optfield::*;
+//! enum Role {
+//!     Admin,
+//!     Owner,
+//!     Guest,
+//! }
+//!
+//! #[optfield(Opt, roles = Role)]
+//! struct MyStruct {
+//!     name: String,
+//!     #[optfield(roles = (Admin, Owner))]
+//!     email: String,
+//!     #[optfield(roles = (Admin))]
+//!     verified: bool,
+//! }
+//!
+//! let mut opt = Opt {
+//!     name: Some("test".to_string()),
+//!     email: Some("test@example.com".to_string()),
+//!     verified: Some(true),
+//! };
+//!
+//! assert!(opt.check(&Role::Admin).is_ok());
+//! assert_eq!(opt.check(&Role::Guest).unwrap_err().fields, vec!["email", "verified"]);
+//!
+//! assert_eq!(opt.restrict(&Role::Owner), vec!["verified"]);
+//! assert_eq!(opt.verified, None);
+//! ```
+//! The error type is named after the opt struct with a `Forbidden` suffix.
+//! `roles = ()` keeps a field from being set by any role.
+//! Other `optfield` attributes on the same struct ignore field roles.
+//!
+//! # Versions
+//! The `versions` argument generates methods to adapt an opt struct to older
+//! or newer schema versions, using the `#[optfield(since = ...)]` and
+//! `#[optfield(until = ...)]` field arguments to give the first and last
+//! versions knowing a field:
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, versions)]
+//! struct MyStruct {
+//!     name: String,
+//!     #[optfield(since = 3)]
+//!     theme: String,
+//!     #[optfield(until = 4)]
+//!     legacy: bool,
+//! }
+//!
+//! let opt = Opt {
+//!     name: Some("test".to_string()),
+//!     theme: Some("dark".to_string()),
+//!     legacy: Some(true),
+//! };
+//!
+//! assert_eq!(opt.min_version(), Some(3));
+//!
+//! let opt = opt.for_version(2);
+//!
+//! assert_eq!(opt.name, Some("test".to_string()));
+//! assert_eq!(opt.theme, None);
+//! assert_eq!(opt.legacy, Some(true));
+//! ```
+//! `for_version` clears the fields the given version doesn't know, and
+//! `min_version` returns the highest `since` of the set fields, or 0. It
+//! returns `None` when that version is above the lowest `until` of the set
+//! fields, since a patch setting fields removed before others were added can't
+//! be represented by any version.
+//! Other `optfield` attributes on the same struct ignore `since` and
+//! `until`.
+//!
+//! # Debug
+//! The `debug` argument generates a `Debug` implementation that only prints
+//! set fields. Fields marked with the `#[optfield(sensitive)]` field argument
+//! are printed as `***`, and their type doesn't need to implement `Debug`:
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, debug)]
+//! struct MyStruct {
+//!     name: String,
+//!     age: u32,
+//!     #[optfield(sensitive)]
+//!     password: String,
+//! }
+//!
+//! let opt = Opt {
+//!     name: Some("test".to_string()),
+//!     age: None,
+//!     password: Some("secret".to_string()),
+//! };
+//!
+//! assert_eq!(format!("{:?}", opt), r#"Opt { name: "test", password: *** }"#);
+//! ```
+//! It conflicts with deriving `Debug` through `attrs`. `get_debug` from
+//! `str_access` also returns `***` for sensitive fields.
+//!
+//! # Default
+//! The `default` argument generates a `Default` implementation, a
+//! `const fn none()` and an associated `EMPTY` constant, all setting every
+//! field to `None`. Unlike `#[derive(Default)]`, they don't require generic
+//! parameters to implement `Default`, and can be used in `static` items:
+//! ```
+//! # use optfield::*;
+//! struct NotDefault;
+//!
+//! #[optfield(Opt, default)]
+//! struct MyStruct<T> {
+//!     value: T,
+//!     name: String,
+//! }
+//!
+//! static EMPTY: Opt<u32> = Opt::EMPTY;
+//!
+//! let opt: Opt<NotDefault> = Opt::default();
+//!
+//! assert!(opt.value.is_none());
+//! assert_eq!(EMPTY.name, None);
+//! ```
+//! It conflicts with deriving `Default` through `attrs`.
+//!
+//! # Views
+//! The `views` argument generates two borrowed views of the fields, `OptRef`
+//! and `OptMut`, whose fields hold `Option<&T>` and `Option<&mut T>`, with the
+//! same visibility as the opt struct. They are created with `as_ref` and
+//! `as_mut` on the opt struct, or with `select` on the original struct, which
+//! borrows only the fields named in a mask, without cloning them:
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, views)]
+//! struct MyStruct {
+//!     text: String,
+//!     tags: Vec<String>,
+//! }
+//!
+//! let original = MyStruct {
+//!     text: "text".to_string(),
+//!     tags: vec!["tag".to_string()],
+//! };
+//!
+//! let view: OptRef = original.select(&["tags"]);
+//!
+//! assert_eq!(view.text, None);
+//! assert_eq!(view.tags, Some(&original.tags));
+//!
+//! let mut opt = Opt {
+//!     text: Some("text".to_string()),
+//!     tags: None,
+//! };
+//!
+//! if let Some(text) = opt.as_mut().text {
+//!     text.push('!');
+//! }
+//!
+//! assert_eq!(opt.as_ref().text.unwrap(), "text!");
+//! ```
+//! `OptRef` is `Copy`. Unit structs and structs without fields can't have
+//! views.
+//!
+//! # Owned
+//! The `owned` argument turns borrowed fields into owned ones, so the opt
+//! struct can outlive the data the original borrows from. `&str` becomes
+//! `String`, `&[T]` becomes `Vec<T>` and any other `&T` becomes
+//! `<T as ToOwned>::Owned`, also inside `Option` fields. Lifetime parameters no
+//! longer used are dropped, unless told otherwise with
+//! [`unused_generics`](#unused-generics), and `From` implementations call
+//! `to_owned`:
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, from, owned)]
+//! struct Request<'a> {
+//!     name: &'a str,
+//!     tags: &'a [u32],
+//! }
+//!
+//! fn store(buffer: &str) -> Opt {
+//!     Opt::from(Request {
+//!         name: buffer,
+//!         tags: &[1, 2],
+//!     })
+//! }
+//!
+//! let opt: Opt = store(&"name".to_string());
+//!
+//! assert_eq!(opt.name, Some("name".to_string()));
+//! assert_eq!(opt.tags, Some(vec![1, 2]));
+//! ```
+//! Will generate:
+//! ```
+//! struct Opt {
+//!     name: Option<String>,
+//!     tags: Option<Vec<u32>>,
+//! }
+//! ```
+//! Since the opt fields no longer have the original's types, `owned` can't be
+//! combined with merge methods, `three_way`, `update_enum`, `matches`,
+//! `field_mask` or `views`.
+//!
+//! # Unused generics
+//! Generic parameters the opt struct fields no longer use, like the lifetimes
+//! of `owned` fields, are handled by the `unused_generics` argument.
+//! `unused_generics = prune` removes them from the opt struct, along with the
+//! bounds mentioning them. `unused_generics = phantom` keeps them with a
+//! `PhantomData` field as visible as the opt struct, `_phantom` or the last
+//! field of tuple structs, which generated methods fill in:
+//! ```
+//! # use optfield::*;
+//! #[optfield(Opt, from, owned, unused_generics = phantom)]
+//! struct Request<'a> {
+//!     name: &'a str,
+//! }
+//!
+//! let opt: Opt<'static> = Opt::from(Request { name: "name" });
+//!
+//! assert_eq!(opt.name.unwrap(), "name");
+//! ```
+//! Will generate:
+//! ```
+//! # use std::marker::PhantomData;
+//! struct Opt<'a> {
+//!     name: Option<String>,
+//!     _phantom: PhantomData<(&'a (),)>,
+//! }
+//! ```
 extern crate proc_macro;
 
 use crate::proc_macro::TokenStream;
@@ -455,13 +1447,31 @@ use syn::{parse_macro_input, ItemStruct};
 
 mod args;
 mod attrs;
+mod cli;
+mod debug;
+mod default;
+mod env;
 mod error;
+mod field_enum;
+mod field_mask;
 mod fields;
 mod from;
 mod generate;
+mod generics;
+mod matches;
 mod merge;
+mod owned;
+mod query;
+mod roles;
+mod sql;
+mod str_access;
+mod three_way;
+mod update_enum;
+mod versions;
+mod views;
 
 use args::Args;
+use attrs::generator::is_optfield_attr;
 use generate::generate;
 
 /// The macro
@@ -471,11 +1481,16 @@ use generate::generate;
 /// [crate documentation]: ./index.html
 #[proc_macro_attribute]
 pub fn optfield(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let item: ItemStruct = parse_macro_input!(item);
+    let mut item: ItemStruct = parse_macro_input!(item);
     let args: Args = parse_macro_input!(attr);
 
     let opt_item = generate(&item, args);
 
+    // field args are still needed if other optfield attributes follow
+    if !item.attrs.iter().any(is_optfield_attr) {
+        fields::args::remove_attrs(&mut item);
+    }
+
     let out = quote! {
         #item
 
@@ -520,7 +1535,7 @@ mod test_util {
     }
 
     pub fn parse_attr(tokens: TokenStream) -> Attribute {
-        parse_attrs(tokens).get(0).unwrap().clone()
+        parse_attrs(tokens).first().unwrap().clone()
     }
 
     pub fn parse_attrs(tokens: TokenStream) -> Vec<Attribute> {
@@ -564,10 +1579,6 @@ mod test_util {
     }
 
     pub fn doc_attrs(attrs: &[Attribute]) -> Vec<Attribute> {
-        attrs
-            .iter()
-            .filter(|a| is_doc_attr(a))
-            .map(|a| a.clone())
-            .collect()
+        attrs.iter().filter(|a| is_doc_attr(a)).cloned().collect()
     }
 }
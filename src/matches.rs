@@ -0,0 +1,114 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ItemStruct;
+
+use crate::args::Args;
+use crate::fields;
+use crate::fields::args::MatchMode;
+
+pub fn generate(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if args.matches {
+        let vis = &opt_item.vis;
+
+        let item_name = &item.ident;
+        let (_, item_ty_generics, _) = item.generics.split_for_impl();
+
+        let opt_name = &opt_item.ident;
+        let (impl_generics, opt_ty_generics, where_clause) = opt_item.generics.split_for_impl();
+
+        let mut bounds = TokenStream::new();
+        let mut checks = TokenStream::new();
+
+        for (i, field) in item.fields.iter().enumerate() {
+            if fields::is_marker(field) {
+                continue;
+            }
+
+            let cfg_attrs = fields::cfg_attrs(field);
+            let field_name = fields::member(i, field);
+            let value_type = fields::value_type(field, args);
+
+            let (bound, matched) = match fields::args::get(field).match_mode {
+                None => (quote!(PartialEq), quote!(value == current)),
+                Some(MatchMode::Contains) => (
+                    quote!(AsRef<str>),
+                    quote! {
+                        AsRef::<str>::as_ref(current).contains(AsRef::<str>::as_ref(value))
+                    },
+                ),
+                Some(MatchMode::Prefix) => (
+                    quote!(AsRef<str>),
+                    quote! {
+                        AsRef::<str>::as_ref(current).starts_with(AsRef::<str>::as_ref(value))
+                    },
+                ),
+                Some(MatchMode::Min) => (quote!(PartialOrd), quote!(current >= value)),
+                Some(MatchMode::Max) => (quote!(PartialOrd), quote!(current <= value)),
+                Some(MatchMode::Range) => (quote!(PartialOrd), quote!(value.contains(current))),
+            };
+
+            bounds.extend(quote! {
+                #value_type: #bound,
+            });
+
+            let check = if fields::is_option(field) && !args.rewrap {
+                quote! {
+                    #cfg_attrs
+                    {
+                        match (&self.#field_name, &original.#field_name) {
+                            (Some(value), Some(current)) => {
+                                if !(#matched) {
+                                    return false;
+                                }
+                            }
+                            (Some(_), None) => return false,
+                            (None, _) => {}
+                        }
+                    }
+                }
+            } else {
+                quote! {
+                    #cfg_attrs
+                    {
+                        if let Some(value) = &self.#field_name {
+                            let current = &original.#field_name;
+
+                            if !(#matched) {
+                                return false;
+                            }
+                        }
+                    }
+                }
+            };
+
+            checks.extend(check);
+        }
+
+        quote! {
+            impl #impl_generics #opt_name #opt_ty_generics #where_clause {
+                #vis fn matches(&self, original: &#item_name #item_ty_generics) -> bool
+                where
+                    #bounds
+                {
+                    #checks
+
+                    true
+                }
+
+                #vis fn filter<'filter, I>(
+                    &'filter self,
+                    iter: I,
+                ) -> impl Iterator<Item = &'filter #item_name #item_ty_generics> + 'filter
+                where
+                    I: IntoIterator<Item = &'filter #item_name #item_ty_generics>,
+                    I::IntoIter: 'filter,
+                    #bounds
+                {
+                    iter.into_iter().filter(move |original| self.matches(original))
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
@@ -1,34 +1,255 @@
-use proc_macro2::{Span, TokenStream};
-use quote::{quote, ToTokens};
-use syn::{Fields, Ident, Index, ItemStruct};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Field, Fields, ItemStruct, Type};
 
-use crate::args::{Args, MergeFnName};
+use crate::args::Args;
 use crate::fields;
+use crate::fields::args::Readonly;
 
 const DEFAULT_FN_NAME: &str = "merge_opt";
-const CFG: &str = "cfg";
+const DEFAULT_FILL_FN_NAME: &str = "fill_opt";
+const DEFAULT_REF_FN_NAME: &str = "merge_opt_ref";
+const DEFAULT_WITH_FN_NAME: &str = "with_opt";
+const DEFAULT_ARC_FN_NAME: &str = "merge_opt_arc";
+const DEFAULT_TRY_FN_NAME: &str = "try_merge_opt";
 
 pub fn generate(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
-    if let Some(merge_fn) = &args.merge {
-        let fn_name = match &merge_fn.name {
-            MergeFnName::Custom(n) => n.clone(),
-            MergeFnName::Default => Ident::new(DEFAULT_FN_NAME, Span::call_site()),
-        };
+    let merge_impl = merge_impl(item, opt_item, args);
+    let fill_impl = fill_impl(item, opt_item, args);
+    let merge_ref_impl = merge_ref_impl(item, opt_item, args);
+    let with_impl = with_impl(item, opt_item, args);
+    let merge_arc_impl = merge_arc_impl(item, opt_item, args);
+    let try_merge_impl = try_merge_impl(item, opt_item, args);
+
+    quote! {
+        #merge_impl
+
+        #fill_impl
+
+        #merge_ref_impl
+
+        #with_impl
+
+        #merge_arc_impl
+
+        #try_merge_impl
+    }
+}
 
+fn merge_impl(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if let Some(merge_fn) = &args.merge {
+        let fn_name = merge_fn.ident(DEFAULT_FN_NAME);
         let fn_vis = &merge_fn.visibility;
 
         let item_name = &item.ident;
-        let item_generics = &item.generics;
+        let (impl_generics, item_ty_generics, where_clause) = item.generics.split_for_impl();
+
+        let opt_name = &opt_item.ident;
+        let (_, opt_ty_generics, _) = opt_item.generics.split_for_impl();
+
+        let fields = field_bindings(&item.fields, args, &quote!(self));
+
+        let rejections = readonly_rejections(&item.fields);
+
+        if rejections.is_empty() {
+            quote! {
+                impl #impl_generics #item_name #item_ty_generics #where_clause {
+                    #fn_vis fn #fn_name(&mut self, opt: #opt_name #opt_ty_generics) {
+                        #fields
+                    }
+                }
+            }
+        } else {
+            quote! {
+                impl #impl_generics #item_name #item_ty_generics #where_clause {
+                    #fn_vis fn #fn_name(
+                        &mut self,
+                        opt: #opt_name #opt_ty_generics,
+                    ) -> Result<(), &'static str> {
+                        #rejections
+
+                        #fields
+
+                        Ok(())
+                    }
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
+
+fn fill_impl(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if let Some(fill_fn) = &args.fill {
+        let fn_name = fill_fn.ident(DEFAULT_FILL_FN_NAME);
+        let fn_vis = &fill_fn.visibility;
+
+        let item_name = &item.ident;
+        let (impl_generics, item_ty_generics, where_clause) = item.generics.split_for_impl();
+
+        let opt_name = &opt_item.ident;
+        let (_, opt_ty_generics, _) = opt_item.generics.split_for_impl();
+
+        let (fields, default_types) = fill_field_bindings(&item.fields, args);
+
+        quote! {
+            impl #impl_generics #item_name #item_ty_generics #where_clause {
+                #fn_vis fn #fn_name(&mut self, opt: #opt_name #opt_ty_generics)
+                where
+                    #(#default_types: Default + PartialEq,)*
+                {
+                    #fields
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
+
+fn merge_ref_impl(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if let Some(merge_ref_fn) = &args.merge_ref {
+        let fn_name = merge_ref_fn.ident(DEFAULT_REF_FN_NAME);
+        let fn_vis = &merge_ref_fn.visibility;
+
+        let item_name = &item.ident;
+        let (impl_generics, item_ty_generics, where_clause) = item.generics.split_for_impl();
+
+        let opt_name = &opt_item.ident;
+        let (_, opt_ty_generics, _) = opt_item.generics.split_for_impl();
+
+        let fields = ref_field_bindings(&item.fields, args);
+        let value_types = merged_fields(&item.fields).map(|(_, f)| fields::value_type(f, args));
+
+        quote! {
+            impl #impl_generics #item_name #item_ty_generics #where_clause {
+                #fn_vis fn #fn_name(&mut self, opt: &#opt_name #opt_ty_generics)
+                where
+                    #(#value_types: Clone,)*
+                {
+                    #fields
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
+
+fn with_impl(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if let Some(with_fn) = &args.with {
+        let fn_name = with_fn.ident(DEFAULT_WITH_FN_NAME);
+        let fn_vis = &with_fn.visibility;
+
+        let item_name = &item.ident;
+        let (impl_generics, item_ty_generics, where_clause) = item.generics.split_for_impl();
 
         let opt_name = &opt_item.ident;
-        let opt_generics = &opt_item.generics;
+        let (_, opt_ty_generics, _) = opt_item.generics.split_for_impl();
 
-        let fields = field_bindings(&item.fields, args);
+        let fields = field_bindings(&item.fields, args, &quote!(self));
 
         quote! {
-            impl#item_generics #item_name#item_generics {
-                #fn_vis fn #fn_name(&mut self, opt: #opt_name#opt_generics) {
+            impl #impl_generics #item_name #item_ty_generics #where_clause {
+                #fn_vis fn #fn_name(mut self, opt: #opt_name #opt_ty_generics) -> Self {
                     #fields
+
+                    self
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
+
+fn merge_arc_impl(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if let Some(merge_arc_fn) = &args.merge_arc {
+        let fn_name = merge_arc_fn.ident(DEFAULT_ARC_FN_NAME);
+        let fn_vis = &merge_arc_fn.visibility;
+
+        let item_name = &item.ident;
+        let (impl_generics, item_ty_generics, where_clause) = item.generics.split_for_impl();
+
+        let opt_name = &opt_item.ident;
+        let (_, opt_ty_generics, _) = opt_item.generics.split_for_impl();
+
+        let changes = change_checks(&item.fields, args);
+        let fields = field_bindings(&item.fields, args, &quote!(this));
+        let value_types = merged_fields(&item.fields).map(|(_, f)| fields::value_type(f, args));
+
+        quote! {
+            impl #impl_generics #item_name #item_ty_generics #where_clause {
+                #fn_vis fn #fn_name(
+                    this: &mut ::std::sync::Arc<Self>,
+                    opt: #opt_name #opt_ty_generics,
+                )
+                where
+                    Self: Clone,
+                    #(#value_types: PartialEq,)*
+                {
+                    let mut changed = false;
+
+                    #changes
+
+                    if changed {
+                        let this = ::std::sync::Arc::make_mut(this);
+
+                        #fields
+                    }
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
+
+fn try_merge_impl(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if let (Some(try_merge_fn), Some(error)) = (&args.try_merge, &args.validate_error) {
+        let fn_name = try_merge_fn.ident(DEFAULT_TRY_FN_NAME);
+        let fn_vis = &try_merge_fn.visibility;
+
+        let item_name = &item.ident;
+        let (impl_generics, item_ty_generics, where_clause) = item.generics.split_for_impl();
+
+        let opt_name = &opt_item.ident;
+        let (_, opt_ty_generics, _) = opt_item.generics.split_for_impl();
+
+        let rejections = readonly_rejections(&item.fields);
+        let field_checks = field_validations(&item.fields);
+
+        let body = match &args.validate {
+            None => field_bindings(&item.fields, args, &quote!(self)),
+            Some(validate) => {
+                let (staged, rollback) = staged_field_bindings(&item.fields, args);
+
+                quote! {
+                    #staged
+
+                    if let Err(e) = #validate(self) {
+                        #rollback
+
+                        return Err(From::from(e));
+                    }
+                }
+            }
+        };
+
+        quote! {
+            impl #impl_generics #item_name #item_ty_generics #where_clause {
+                #fn_vis fn #fn_name(
+                    &mut self,
+                    opt: #opt_name #opt_ty_generics,
+                ) -> Result<(), #error> {
+                    #rejections
+
+                    #field_checks
+
+                    #body
+
+                    Ok(())
                 }
             }
         }
@@ -37,43 +258,214 @@ pub fn generate(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenS
     }
 }
 
-fn field_bindings(fields: &Fields, args: &Args) -> TokenStream {
+/// Runs the field validators on the opt values that are `Some`.
+fn field_validations(fields: &Fields) -> TokenStream {
     let mut tokens = TokenStream::new();
 
-    for (i, field) in fields.iter().enumerate() {
-        let mut cfg_attrs = TokenStream::new();
+    for (i, field) in merged_fields(fields) {
+        if let Some(validate) = fields::args::get(field).validate {
+            let cfg_attrs = fields::cfg_attrs(field);
+            let field_name = fields::member(i, field);
 
-        for attr in field.attrs.iter() {
-            if attr.path().is_ident(CFG) {
-                attr.to_tokens(&mut cfg_attrs);
+            tokens.extend(quote! {
+                #cfg_attrs
+                {
+                    if let Some(value) = &opt.#field_name {
+                        #validate(value)?;
+                    }
+                }
+            });
+        }
+    }
+
+    tokens
+}
+
+/// Bindings that keep the replaced values, along with the bindings that put
+/// them back.
+fn staged_field_bindings(fields: &Fields, args: &Args) -> (TokenStream, TokenStream) {
+    let mut staged = TokenStream::new();
+    let mut rollback = TokenStream::new();
+
+    for (i, field) in merged_fields(fields) {
+        let cfg_attrs = fields::cfg_attrs(field);
+        let field_name = fields::member(i, field);
+        let old_value = format_ident!("old_{}", i);
+
+        let staged_tokens = if fields::is_option(field) && !args.rewrap {
+            let guard = merge_if_call(field, quote!(&self.#field_name), quote!(&opt.#field_name))
+                .map(|call| quote!(&& #call));
+
+            quote! {
+                #cfg_attrs
+                let #old_value = if opt.#field_name.is_some() #guard {
+                    Some(::std::mem::replace(&mut self.#field_name, opt.#field_name))
+                } else {
+                    None
+                };
+            }
+        } else {
+            let guard = merge_if_call(field, quote!(&self.#field_name), quote!(&value))
+                .map(|call| quote!(if #call));
+
+            quote! {
+                #cfg_attrs
+                let #old_value = match opt.#field_name {
+                    Some(value) #guard => Some(::std::mem::replace(&mut self.#field_name, value)),
+                    _ => None,
+                };
             }
+        };
+
+        staged.extend(staged_tokens);
+
+        rollback.extend(quote! {
+            #cfg_attrs
+            {
+                if let Some(value) = #old_value {
+                    self.#field_name = value;
+                }
+            }
+        });
+    }
+
+    (staged, rollback)
+}
+
+/// Returns the name of the first `readonly = reject` field that is `Some`,
+/// converted into the error type.
+fn readonly_rejections(fields: &Fields) -> TokenStream {
+    let mut tokens = TokenStream::new();
+
+    for (i, field) in fields
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| !fields::is_marker(f))
+    {
+        if let Some(Readonly::Reject) = fields::args::get(field).readonly {
+            let cfg_attrs = fields::cfg_attrs(field);
+            let field_name = fields::member(i, field);
+            let field_name_str = fields::name(i, field);
+
+            tokens.extend(quote! {
+                #cfg_attrs
+                {
+                    if opt.#field_name.is_some() {
+                        return Err(From::from(#field_name_str));
+                    }
+                }
+            });
         }
+    }
+
+    tokens
+}
 
-        let field_name = match &field.ident {
-            // means that original item is a tuple struct
-            None => {
-                let index = Index::from(i);
+/// Call to the `merge_if` predicate of the field, if it has one.
+fn merge_if_call(field: &Field, current: TokenStream, new: TokenStream) -> Option<TokenStream> {
+    fields::args::get(field)
+        .merge_if
+        .map(|merge_if| quote!(#merge_if(#current, #new)))
+}
 
-                quote!(#index)
+/// Wraps the assignment of a field in its `merge_if` predicate, if it has one.
+pub fn guarded(
+    field: &Field,
+    current: TokenStream,
+    new: TokenStream,
+    assign: TokenStream,
+) -> TokenStream {
+    match merge_if_call(field, current, new) {
+        Some(call) => quote! {
+            if #call {
+                #assign
+            }
+        },
+        None => assign,
+    }
+}
+
+/// Fields set by merge methods, leaving out readonly and marker fields.
+fn merged_fields(fields: &Fields) -> impl Iterator<Item = (usize, &Field)> {
+    fields.iter().enumerate().filter(|(_, field)| {
+        fields::args::get(field).readonly.is_none() && !fields::is_marker(field)
+    })
+}
+
+/// Checks whether merging would change any field of `this`, setting `changed`.
+fn change_checks(fields: &Fields, args: &Args) -> TokenStream {
+    let mut tokens = TokenStream::new();
+
+    for (i, field) in merged_fields(fields) {
+        let cfg_attrs = fields::cfg_attrs(field);
+        let field_name = fields::member(i, field);
+
+        let field_tokens = if fields::is_option(field) && !args.rewrap {
+            let guard = merge_if_call(field, quote!(&this.#field_name), quote!(&opt.#field_name))
+                .map(|call| quote!(&& #call));
+
+            quote! {
+                #cfg_attrs
+                {
+                    changed = changed || (opt.#field_name.is_some() && opt.#field_name != this.#field_name #guard);
+                }
+            }
+        } else {
+            let guard = merge_if_call(field, quote!(&this.#field_name), quote!(value))
+                .map(|call| quote!(&& #call));
+
+            quote! {
+                #cfg_attrs
+                {
+                    changed = changed || matches!(&opt.#field_name, Some(value) if *value != this.#field_name #guard);
+                }
             }
-            Some(ident) => quote!(#ident),
         };
 
+        tokens.extend(field_tokens);
+    }
+
+    tokens
+}
+
+/// Bindings that set the values of `target` fields to the opt values that are
+/// `Some`.
+fn field_bindings(fields: &Fields, args: &Args, target: &TokenStream) -> TokenStream {
+    let mut tokens = TokenStream::new();
+
+    for (i, field) in merged_fields(fields) {
+        let cfg_attrs = fields::cfg_attrs(field);
+        let field_name = fields::member(i, field);
+
         let field_tokens = if fields::is_option(field) && !args.rewrap {
+            let assign = guarded(
+                field,
+                quote!(&#target.#field_name),
+                quote!(&opt.#field_name),
+                quote!(#target.#field_name = opt.#field_name;),
+            );
+
             quote! {
                 #cfg_attrs
                 {
                     if opt.#field_name.is_some() {
-                        self.#field_name = opt.#field_name;
+                        #assign
                     }
                 }
             }
         } else {
+            let assign = guarded(
+                field,
+                quote!(&#target.#field_name),
+                quote!(&value),
+                quote!(#target.#field_name = value;),
+            );
+
             quote! {
                 #cfg_attrs
                 {
                     if let Some(value) = opt.#field_name {
-                        self.#field_name = value
+                        #assign
                     }
                 }
             }
@@ -84,3 +476,131 @@ fn field_bindings(fields: &Fields, args: &Args) -> TokenStream {
 
     tokens
 }
+
+/// Bindings that clone the values of a borrowed opt struct.
+fn ref_field_bindings(fields: &Fields, args: &Args) -> TokenStream {
+    let mut tokens = TokenStream::new();
+
+    for (i, field) in merged_fields(fields) {
+        let cfg_attrs = fields::cfg_attrs(field);
+        let field_name = fields::member(i, field);
+
+        let field_tokens = if fields::is_option(field) && !args.rewrap {
+            let assign = guarded(
+                field,
+                quote!(&self.#field_name),
+                quote!(&opt.#field_name),
+                quote!(self.#field_name = Clone::clone(&opt.#field_name);),
+            );
+
+            quote! {
+                #cfg_attrs
+                {
+                    if opt.#field_name.is_some() {
+                        #assign
+                    }
+                }
+            }
+        } else {
+            let assign = guarded(
+                field,
+                quote!(&self.#field_name),
+                quote!(value),
+                quote!(self.#field_name = Clone::clone(value);),
+            );
+
+            quote! {
+                #cfg_attrs
+                {
+                    if let Some(value) = &opt.#field_name {
+                        #assign
+                    }
+                }
+            }
+        };
+
+        tokens.extend(field_tokens);
+    }
+
+    tokens
+}
+
+/// Bindings that only set fields considered unset: `None` for `Option` fields
+/// and the default value for `fill_default` fields.
+///
+/// Also returns the types of `fill_default` fields.
+fn fill_field_bindings(fields: &Fields, args: &Args) -> (TokenStream, Vec<Type>) {
+    let mut tokens = TokenStream::new();
+    let mut default_types = Vec::new();
+
+    for (i, field) in merged_fields(fields) {
+        let cfg_attrs = fields::cfg_attrs(field);
+        let field_name = fields::member(i, field);
+
+        let field_tokens = if fields::is_option(field) {
+            if args.rewrap {
+                let assign = guarded(
+                    field,
+                    quote!(&self.#field_name),
+                    quote!(&value),
+                    quote!(self.#field_name = value;),
+                );
+
+                quote! {
+                    #cfg_attrs
+                    {
+                        if self.#field_name.is_none() {
+                            if let Some(value) = opt.#field_name {
+                                #assign
+                            }
+                        }
+                    }
+                }
+            } else {
+                let assign = guarded(
+                    field,
+                    quote!(&self.#field_name),
+                    quote!(&opt.#field_name),
+                    quote!(self.#field_name = opt.#field_name;),
+                );
+
+                quote! {
+                    #cfg_attrs
+                    {
+                        if self.#field_name.is_none() {
+                            #assign
+                        }
+                    }
+                }
+            }
+        } else if fields::args::get(field).fill_default {
+            let ty = &field.ty;
+
+            default_types.push(ty.clone());
+
+            let assign = guarded(
+                field,
+                quote!(&self.#field_name),
+                quote!(&value),
+                quote!(self.#field_name = value;),
+            );
+
+            quote! {
+                #cfg_attrs
+                {
+                    if let Some(value) = opt.#field_name {
+                        if self.#field_name == <#ty as Default>::default() {
+                            #assign
+                        }
+                    }
+                }
+            }
+        } else {
+            continue;
+        };
+
+        tokens.extend(field_tokens);
+    }
+
+    (tokens, default_types)
+}
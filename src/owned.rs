@@ -0,0 +1,198 @@
+use std::collections::HashSet;
+
+use syn::{parse_quote, Field, ItemStruct, Type, TypePath, WherePredicate};
+
+use crate::args::Args;
+use crate::fields;
+use crate::generics;
+
+const STR: &str = "str";
+
+/// With the `owned` argument, a copy of the original whose borrowed field
+/// types are replaced by owned ones. Otherwise a plain copy of the original.
+pub fn item(original: &ItemStruct, args: &Args) -> ItemStruct {
+    let mut item = original.clone();
+
+    if !args.owned {
+        return item;
+    }
+
+    let predicates = predicates(original);
+
+    for field in item.fields.iter_mut() {
+        let owned = if fields::is_option(field) {
+            fields::option_inner(&field.ty)
+                .and_then(owned_type)
+                .map(|(owned, _)| parse_quote!(Option<#owned>))
+        } else {
+            owned_type(&field.ty).map(|(owned, _)| owned)
+        };
+
+        if let Some(owned) = owned {
+            field.ty = owned;
+        }
+    }
+
+    if !predicates.is_empty() {
+        item.generics
+            .make_where_clause()
+            .predicates
+            .extend(predicates);
+    }
+
+    item
+}
+
+/// Whether the field, or the value of an `Option` field, is a reference that
+/// the `owned` argument turns into an owned type.
+pub fn is_borrowed(field: &Field) -> bool {
+    let ty = if fields::is_option(field) {
+        fields::option_inner(&field.ty)
+    } else {
+        Some(&field.ty)
+    };
+
+    matches!(ty, Some(Type::Reference(_)))
+}
+
+/// Bounds needed to turn the borrowed fields of the original into owned ones.
+/// Bounds that don't involve type parameters always hold and are left out.
+fn predicates(original: &ItemStruct) -> Vec<WherePredicate> {
+    let type_params: HashSet<String> = original
+        .generics
+        .type_params()
+        .map(|param| param.ident.to_string())
+        .collect();
+
+    let mut predicates = Vec::new();
+
+    // bounds can't be cfg'd out along with their fields
+    for field in original
+        .fields
+        .iter()
+        .filter(|f| fields::cfg_attrs(f).is_empty())
+    {
+        let ty = if fields::is_option(field) {
+            fields::option_inner(&field.ty)
+        } else {
+            Some(&field.ty)
+        };
+
+        if let Some((_, predicate)) = ty.and_then(owned_type) {
+            if generics::mentions(&predicate, &type_params) {
+                predicates.push(predicate);
+            }
+        }
+    }
+
+    predicates
+}
+
+/// Owned type replacing a reference type and the bound needed to convert to
+/// it: `&str` becomes `String`, `&[T]` becomes `Vec<T>` and `&T` becomes
+/// `<T as ToOwned>::Owned`.
+fn owned_type(ty: &Type) -> Option<(Type, WherePredicate)> {
+    let borrowed = match ty {
+        Type::Reference(reference) => &*reference.elem,
+        _ => return None,
+    };
+
+    let owned: Type = match borrowed {
+        Type::Path(TypePath { qself: None, path }) if path.is_ident(STR) => {
+            parse_quote!(::std::string::String)
+        }
+        Type::Slice(slice) => {
+            let elem = &slice.elem;
+
+            parse_quote!(::std::vec::Vec<#elem>)
+        }
+        _ => {
+            return Some((
+                parse_quote!(<#borrowed as ::std::borrow::ToOwned>::Owned),
+                parse_quote!(#borrowed: ::std::borrow::ToOwned),
+            ))
+        }
+    };
+
+    let predicate = parse_quote!(#borrowed: ::std::borrow::ToOwned<Owned = #owned>);
+
+    Some((owned, predicate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use quote::quote;
+
+    use crate::test_util::*;
+
+    #[test]
+    fn owned_types() {
+        let (original, args) = parse_item_and_args(
+            quote! {
+                struct S<'a, 'b, T> {
+                    text: &'a str,
+                    tags: &'a [T],
+                    value: &'b mut T,
+                    optional: Option<&'a str>,
+                    number: i32
+                }
+            },
+            quote! {
+                Opt,
+                owned
+            },
+        );
+
+        let expected = parse_item(quote! {
+            struct S<'a, 'b, T>
+            where
+                [T]: ::std::borrow::ToOwned<Owned = ::std::vec::Vec<T>>,
+                T: ::std::borrow::ToOwned
+            {
+                text: ::std::string::String,
+                tags: ::std::vec::Vec<T>,
+                value: <T as ::std::borrow::ToOwned>::Owned,
+                optional: Option<::std::string::String>,
+                number: i32
+            }
+        });
+
+        let item = item(&original, &args);
+
+        assert_eq!(item.generics, expected.generics);
+        assert_eq!(field_types(item.fields), field_types(expected.fields));
+    }
+
+    #[test]
+    fn not_owned() {
+        let (original, args) = parse_item_and_args(
+            quote! {
+                struct S<'a> {
+                    text: &'a str
+                }
+            },
+            quote! {
+                Opt
+            },
+        );
+
+        assert_eq!(item(&original, &args), original);
+    }
+
+    #[test]
+    fn borrowed_fields() {
+        let item = parse_item(quote! {
+            struct S<'a> {
+                text: &'a str,
+                optional: Option<&'a str>,
+                owned: String
+            }
+        });
+
+        let borrowed: Vec<bool> = item.fields.iter().map(is_borrowed).collect();
+
+        assert_eq!(borrowed, vec![true, true, false]);
+    }
+}
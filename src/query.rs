@@ -0,0 +1,201 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ItemStruct;
+
+use crate::args::Args;
+use crate::fields;
+
+pub fn generate(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if args.query {
+        let vis = &opt_item.vis;
+
+        let opt_name = &opt_item.ident;
+        let (impl_generics, opt_ty_generics, where_clause) = opt_item.generics.split_for_impl();
+
+        let error_name = format_ident!("{}QueryError", opt_name);
+
+        let mut parsed_types = Vec::new();
+        let mut empty_fields = TokenStream::new();
+        let mut key_matches = TokenStream::new();
+        let mut pairs = TokenStream::new();
+
+        for (i, field) in item.fields.iter().enumerate() {
+            let cfg_attrs = fields::cfg_attrs(field);
+            let field_name = fields::member(i, field);
+
+            if fields::is_marker(field) {
+                let value = fields::marker_value(field);
+
+                empty_fields.extend(quote! {
+                    #cfg_attrs
+                    #field_name: #value,
+                });
+
+                continue;
+            }
+
+            let key = fields::name(i, field);
+            let (parsed_type, value) = fields::parsed_type(field, args);
+
+            empty_fields.extend(quote! {
+                #cfg_attrs
+                #field_name: None,
+            });
+
+            key_matches.extend(quote! {
+                #cfg_attrs
+                #key => {
+                    if opt.#field_name.is_some() {
+                        return Err(#error_name::DuplicateKey(key));
+                    }
+
+                    let value = value.parse::<#parsed_type>().map_err(|e| #error_name::InvalidValue {
+                        key: #key,
+                        message: e.to_string(),
+                    })?;
+
+                    opt.#field_name = Some(#value);
+                }
+            });
+
+            // rewrapped fields set to Some(None) can't be expressed
+            let pattern = if fields::is_option(field) && args.rewrap {
+                quote!(Some(Some(value)))
+            } else {
+                quote!(Some(value))
+            };
+
+            pairs.extend(quote! {
+                #cfg_attrs
+                {
+                    if let #pattern = &self.#field_name {
+                        pairs.push((#key, value.to_string()));
+                    }
+                }
+            });
+
+            parsed_types.push(parsed_type);
+        }
+
+        quote! {
+            #[derive(Clone, Debug, PartialEq, Eq)]
+            #vis enum #error_name {
+                InvalidEncoding(String),
+                DuplicateKey(String),
+                InvalidValue {
+                    key: &'static str,
+                    message: String,
+                },
+            }
+
+            impl ::std::fmt::Display for #error_name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    match self {
+                        #error_name::InvalidEncoding(part) => {
+                            write!(f, "invalid percent-encoding in `{}`", part)
+                        }
+                        #error_name::DuplicateKey(key) => write!(f, "duplicate key `{}`", key),
+                        #error_name::InvalidValue { key, message } => {
+                            write!(f, "invalid value for `{}`: {}", key, message)
+                        }
+                    }
+                }
+            }
+
+            impl ::std::error::Error for #error_name {}
+
+            impl #impl_generics #opt_name #opt_ty_generics #where_clause {
+                #vis fn from_query(query: &str) -> Result<Self, #error_name>
+                where
+                    #(
+                        #parsed_types: ::std::str::FromStr,
+                        <#parsed_types as ::std::str::FromStr>::Err: ::std::fmt::Display,
+                    )*
+                {
+                    fn decode(part: &str) -> Result<String, #error_name> {
+                        let invalid = || #error_name::InvalidEncoding(part.to_string());
+
+                        let mut bytes = Vec::with_capacity(part.len());
+                        let mut iter = part.bytes();
+
+                        while let Some(byte) = iter.next() {
+                            match byte {
+                                b'+' => bytes.push(b' '),
+                                b'%' => {
+                                    let mut hex = [0; 2];
+
+                                    for digit in hex.iter_mut() {
+                                        *digit = match iter.next() {
+                                            Some(byte) => (byte as char).to_digit(16).ok_or_else(invalid)? as u8,
+                                            None => return Err(invalid()),
+                                        };
+                                    }
+
+                                    bytes.push(hex[0] * 16 + hex[1]);
+                                }
+                                byte => bytes.push(byte),
+                            }
+                        }
+
+                        String::from_utf8(bytes).map_err(|_| invalid())
+                    }
+
+                    let mut opt = #opt_name { #empty_fields };
+
+                    let query = query.strip_prefix('?').unwrap_or(query);
+
+                    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+                        let (key, value) = match pair.split_once('=') {
+                            Some((key, value)) => (decode(key)?, decode(value)?),
+                            None => (decode(pair)?, String::new()),
+                        };
+
+                        match key.as_str() {
+                            #key_matches
+                            _ => {}
+                        }
+                    }
+
+                    Ok(opt)
+                }
+
+                #vis fn to_query(&self) -> String
+                where
+                    #(#parsed_types: ::std::fmt::Display,)*
+                {
+                    fn encode(part: &str, query: &mut String) {
+                        for byte in part.bytes() {
+                            match byte {
+                                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                                    query.push(byte as char)
+                                }
+                                b' ' => query.push('+'),
+                                byte => query.push_str(&format!("%{:02X}", byte)),
+                            }
+                        }
+                    }
+
+                    let mut pairs: Vec<(&str, String)> = Vec::new();
+
+                    #pairs
+
+                    let mut query = String::new();
+
+                    for (key, value) in pairs {
+                        if !query.is_empty() {
+                            query.push('&');
+                        }
+
+                        encode(key, &mut query);
+                        query.push('=');
+                        encode(&value, &mut query);
+                    }
+
+                    query
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
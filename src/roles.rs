@@ -0,0 +1,98 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ItemStruct;
+
+use crate::args::Args;
+use crate::fields;
+
+pub fn generate(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    // without the roles argument, field roles are ignored (see FieldArgs)
+    if let Some(role_type) = &args.roles {
+        let vis = &opt_item.vis;
+
+        let opt_name = &opt_item.ident;
+        let (impl_generics, opt_ty_generics, where_clause) = opt_item.generics.split_for_impl();
+
+        let forbidden_name = format_ident!("{}Forbidden", opt_name);
+
+        let mut restrictions = TokenStream::new();
+        let mut checks = TokenStream::new();
+
+        // fields without roles can be set by any role
+        for (i, field) in item.fields.iter().enumerate() {
+            if fields::is_marker(field) {
+                continue;
+            }
+
+            if let Some(roles) = fields::args::get(field).roles {
+                let cfg_attrs = fields::cfg_attrs(field);
+                let field_name = fields::member(i, field);
+                let field_name_str = fields::name(i, field);
+
+                let allowed = if roles.is_empty() {
+                    quote!(false)
+                } else {
+                    quote!(matches!(role, #(#role_type::#roles)|*))
+                };
+
+                restrictions.extend(quote! {
+                    #cfg_attrs
+                    {
+                        if self.#field_name.is_some() && !#allowed {
+                            self.#field_name = None;
+                            stripped.push(#field_name_str);
+                        }
+                    }
+                });
+
+                checks.extend(quote! {
+                    #cfg_attrs
+                    {
+                        if self.#field_name.is_some() && !#allowed {
+                            fields.push(#field_name_str);
+                        }
+                    }
+                });
+            }
+        }
+
+        quote! {
+            #[derive(Clone, Debug, PartialEq, Eq)]
+            #vis struct #forbidden_name {
+                #vis fields: Vec<&'static str>,
+            }
+
+            impl ::std::fmt::Display for #forbidden_name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    write!(f, "forbidden fields: {}", self.fields.join(", "))
+                }
+            }
+
+            impl ::std::error::Error for #forbidden_name {}
+
+            impl #impl_generics #opt_name #opt_ty_generics #where_clause {
+                #vis fn restrict(&mut self, role: &#role_type) -> Vec<&'static str> {
+                    let mut stripped = Vec::new();
+
+                    #restrictions
+
+                    stripped
+                }
+
+                #vis fn check(&self, role: &#role_type) -> Result<(), #forbidden_name> {
+                    let mut fields = Vec::new();
+
+                    #checks
+
+                    if fields.is_empty() {
+                        Ok(())
+                    } else {
+                        Err(#forbidden_name { fields })
+                    }
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
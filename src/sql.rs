@@ -0,0 +1,82 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ItemStruct;
+
+use crate::args::{Args, Placeholder};
+use crate::fields;
+
+pub fn generate(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if let Some(placeholder) = &args.sql {
+        let vis = &opt_item.vis;
+
+        let opt_name = &opt_item.ident;
+        let (impl_generics, opt_ty_generics, where_clause) = opt_item.generics.split_for_impl();
+
+        let placeholder = match placeholder {
+            Placeholder::Question => quote!(sql.push('?')),
+            Placeholder::Numbered => quote!(sql.push_str(&format!("${}", values.len()))),
+        };
+
+        let mut value_types = Vec::new();
+        let mut assignments = TokenStream::new();
+
+        for (i, field) in item.fields.iter().enumerate() {
+            if fields::is_marker(field) {
+                continue;
+            }
+
+            let cfg_attrs = fields::cfg_attrs(field);
+            let field_name = fields::member(i, field);
+
+            let column = match fields::args::get(field).column {
+                Some(column) => column.value(),
+                None => fields::name(i, field),
+            };
+
+            assignments.extend(quote! {
+                #cfg_attrs
+                {
+                    if let Some(value) = &self.#field_name {
+                        if values.is_empty() {
+                            sql.push_str("UPDATE ");
+                            sql.push_str(table);
+                            sql.push_str(" SET ");
+                        } else {
+                            sql.push_str(", ");
+                        }
+
+                        values.push(value);
+
+                        sql.push_str(#column);
+                        sql.push_str(" = ");
+                        #placeholder;
+                    }
+                }
+            });
+
+            value_types.push(fields::value_type(field, args));
+        }
+
+        quote! {
+            impl #impl_generics #opt_name #opt_ty_generics #where_clause {
+                #vis fn to_update(&self, table: &str) -> Option<(String, Vec<&dyn ToSqlValue>)>
+                where
+                    #(#value_types: ToSqlValue,)*
+                {
+                    let mut sql = String::new();
+                    let mut values: Vec<&dyn ToSqlValue> = Vec::new();
+
+                    #assignments
+
+                    if values.is_empty() {
+                        None
+                    } else {
+                        Some((sql, values))
+                    }
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
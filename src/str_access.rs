@@ -0,0 +1,121 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ItemStruct;
+
+use crate::args::Args;
+use crate::fields;
+
+pub fn generate(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if args.str_access {
+        let vis = &opt_item.vis;
+
+        let opt_name = &opt_item.ident;
+        let (impl_generics, opt_ty_generics, where_clause) = opt_item.generics.split_for_impl();
+
+        let error_name = format_ident!("{}SetError", opt_name);
+
+        let mut parsed_types = Vec::new();
+        let mut debug_types = Vec::new();
+        let mut setters = TokenStream::new();
+        let mut getters = TokenStream::new();
+
+        for (i, field) in item.fields.iter().enumerate() {
+            if fields::is_marker(field) {
+                continue;
+            }
+
+            let cfg_attrs = fields::cfg_attrs(field);
+            let field_name = fields::member(i, field);
+            let field_name_str = fields::name(i, field);
+            let field_args = fields::args::get(field);
+            let aliases = field_args.aliases;
+
+            let (parsed_type, value) = fields::parsed_type(field, args);
+
+            setters.extend(quote! {
+                #cfg_attrs
+                #field_name_str #(| #aliases)* => {
+                    let value = value.parse::<#parsed_type>().map_err(|e| #error_name::InvalidValue {
+                        key: #field_name_str,
+                        message: e.to_string(),
+                    })?;
+
+                    self.#field_name = Some(#value);
+
+                    Ok(())
+                }
+            });
+
+            // sensitive values are hidden like in the debug output
+            let debug = if field_args.sensitive {
+                quote!(self.#field_name.as_ref().map(|_| "***".to_string()))
+            } else {
+                debug_types.push(fields::value_type(field, args));
+
+                quote!(self.#field_name.as_ref().map(|value| format!("{:?}", value)))
+            };
+
+            getters.extend(quote! {
+                #cfg_attrs
+                #field_name_str #(| #aliases)* => #debug,
+            });
+
+            parsed_types.push(parsed_type);
+        }
+
+        quote! {
+            #[derive(Clone, Debug, PartialEq, Eq)]
+            #vis enum #error_name {
+                UnknownKey(String),
+                InvalidValue {
+                    key: &'static str,
+                    message: String,
+                },
+            }
+
+            impl ::std::fmt::Display for #error_name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    match self {
+                        #error_name::UnknownKey(key) => write!(f, "unknown key `{}`", key),
+                        #error_name::InvalidValue { key, message } => {
+                            write!(f, "invalid value for `{}`: {}", key, message)
+                        }
+                    }
+                }
+            }
+
+            impl ::std::error::Error for #error_name {}
+
+            impl #impl_generics #opt_name #opt_ty_generics #where_clause {
+                #vis fn set_str(&mut self, key: &str, value: &str) -> Result<(), #error_name>
+                where
+                    #(
+                        #parsed_types: ::std::str::FromStr,
+                        <#parsed_types as ::std::str::FromStr>::Err: ::std::fmt::Display,
+                    )*
+                {
+                    match key {
+                        #setters
+                        _ => {
+                            let _ = value;
+
+                            Err(#error_name::UnknownKey(key.to_string()))
+                        }
+                    }
+                }
+
+                #vis fn get_debug(&self, key: &str) -> Option<String>
+                where
+                    #(#debug_types: ::std::fmt::Debug,)*
+                {
+                    match key {
+                        #getters
+                        _ => None,
+                    }
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
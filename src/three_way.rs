@@ -0,0 +1,201 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse2, Fields, ItemStruct};
+
+use crate::args::Args;
+use crate::error::unexpected;
+use crate::fields;
+
+const DEFAULT_FN_NAME: &str = "three_way";
+
+pub fn generate(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if let Some(three_way_fn) = &args.three_way {
+        let fn_name = three_way_fn.ident(DEFAULT_FN_NAME);
+        let fn_vis = &three_way_fn.visibility;
+
+        let item_name = &item.ident;
+        let (_, item_ty_generics, _) = item.generics.split_for_impl();
+
+        let opt_name = &opt_item.ident;
+        let (impl_generics, opt_ty_generics, where_clause) = opt_item.generics.split_for_impl();
+
+        let conflicts = conflicts_struct(item, opt_item, args);
+        let conflicts_name = &conflicts.ident;
+        let conflicts_name_str = conflicts_name.to_string();
+
+        let mut value_types = Vec::new();
+        let mut empty_conflicts = TokenStream::new();
+        let mut merged_fields = TokenStream::new();
+        let mut conflict_names = TokenStream::new();
+
+        for (i, field) in item.fields.iter().enumerate() {
+            let cfg_attrs = fields::cfg_attrs(field);
+            let field_name = fields::member(i, field);
+            let field_name_str = fields::name(i, field);
+
+            if fields::is_marker(field) {
+                let value = fields::marker_value(field);
+
+                empty_conflicts.extend(quote! {
+                    #cfg_attrs
+                    #field_name: #value,
+                });
+
+                merged_fields.extend(quote! {
+                    #cfg_attrs
+                    #field_name: #value,
+                });
+
+                continue;
+            }
+
+            value_types.push(fields::value_type(field, args));
+
+            // ours and theirs only conflict if both differ from base
+            let (ours_is_base, theirs_is_base) = if fields::is_option(field) && !args.rewrap {
+                (
+                    quote!(base.#field_name.as_ref() == Some(&ours)),
+                    quote!(base.#field_name.as_ref() == Some(&theirs)),
+                )
+            } else {
+                (
+                    quote!(ours == base.#field_name),
+                    quote!(theirs == base.#field_name),
+                )
+            };
+
+            empty_conflicts.extend(quote! {
+                #cfg_attrs
+                #field_name: None,
+            });
+
+            merged_fields.extend(quote! {
+                #cfg_attrs
+                #field_name: match (ours.#field_name, theirs.#field_name) {
+                    (Some(ours), Some(theirs)) => {
+                        if ours == theirs || #theirs_is_base {
+                            Some(ours)
+                        } else if #ours_is_base {
+                            Some(theirs)
+                        } else {
+                            conflicts.#field_name = Some((ours, theirs));
+                            conflicted = true;
+                            None
+                        }
+                    }
+                    (ours, theirs) => ours.or(theirs),
+                },
+            });
+
+            conflict_names.extend(quote! {
+                #cfg_attrs
+                {
+                    if self.#field_name.is_some() {
+                        names.push(#field_name_str);
+                    }
+                }
+            });
+        }
+
+        quote! {
+            #conflicts
+
+            impl #impl_generics #conflicts_name #opt_ty_generics #where_clause {
+                #fn_vis fn fields(&self) -> Vec<&'static str> {
+                    let mut names = Vec::new();
+
+                    #conflict_names
+
+                    names
+                }
+            }
+
+            impl #impl_generics ::std::fmt::Debug for #conflicts_name #opt_ty_generics #where_clause {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    f.debug_struct(#conflicts_name_str)
+                        .field("fields", &self.fields())
+                        .finish()
+                }
+            }
+
+            impl #impl_generics ::std::fmt::Display for #conflicts_name #opt_ty_generics #where_clause {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    write!(f, "conflicting fields: {}", self.fields().join(", "))
+                }
+            }
+
+            impl #impl_generics ::std::error::Error for #conflicts_name #opt_ty_generics #where_clause {}
+
+            impl #impl_generics #opt_name #opt_ty_generics #where_clause {
+                #fn_vis fn #fn_name(
+                    base: &#item_name #item_ty_generics,
+                    ours: Self,
+                    theirs: Self,
+                ) -> Result<Self, #conflicts_name #opt_ty_generics>
+                where
+                    #(#value_types: PartialEq,)*
+                {
+                    let mut conflicts = #conflicts_name {
+                        #empty_conflicts
+                    };
+                    let mut conflicted = false;
+
+                    let merged = Self {
+                        #merged_fields
+                    };
+
+                    if conflicted {
+                        Err(conflicts)
+                    } else {
+                        Ok(merged)
+                    }
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
+
+/// Struct with the same shape as the opt struct, holding both values of
+/// conflicting fields.
+fn conflicts_struct(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> ItemStruct {
+    let mut conflicts = opt_item.clone();
+
+    conflicts.ident = format_ident!("{}Conflicts", opt_item.ident);
+    conflicts.attrs = Vec::new();
+
+    let conflicts_name = conflicts.ident.clone();
+
+    let item_fields = item.fields.iter();
+    let conflict_fields = match &mut conflicts.fields {
+        Fields::Named(named) => named.named.iter_mut(),
+        Fields::Unnamed(unnamed) => unnamed.unnamed.iter_mut(),
+        Fields::Unit => return conflicts,
+    };
+
+    for (conflict_field, item_field) in conflict_fields.zip(item_fields) {
+        let value_type = fields::value_type(item_field, args);
+
+        conflict_field.attrs = item_field
+            .attrs
+            .iter()
+            .filter(|a| fields::is_cfg_attr(a))
+            .cloned()
+            .collect();
+
+        if fields::is_marker(item_field) {
+            continue;
+        }
+
+        conflict_field.ty =
+            parse2(quote!(Option<(#value_type, #value_type)>)).unwrap_or_else(|e| {
+                panic!(
+                    "{}",
+                    unexpected(format!("generating {} fields", conflicts_name), e)
+                )
+            });
+    }
+
+    conflicts
+}
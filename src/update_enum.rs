@@ -0,0 +1,160 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ItemStruct;
+
+use crate::args::Args;
+use crate::fields;
+use crate::generics;
+use crate::merge;
+
+pub fn generate(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if let Some(enum_attrs) = &args.update_enum {
+        if let Err(e) = fields::check_variant_idents(&item.fields) {
+            return e.to_compile_error();
+        }
+
+        let vis = &opt_item.vis;
+
+        let item_name = &item.ident;
+        let opt_name = &opt_item.ident;
+        let (impl_generics, item_ty_generics, where_clause) = item.generics.split_for_impl();
+        let (_, opt_ty_generics, _) = opt_item.generics.split_for_impl();
+
+        let enum_name = format_ident!("{}Update", item_name);
+
+        let mut variants = TokenStream::new();
+        let mut updates = TokenStream::new();
+        let mut collected = TokenStream::new();
+        let mut applied = TokenStream::new();
+        let mut empty_fields = TokenStream::new();
+
+        for (i, field) in item.fields.iter().enumerate() {
+            let cfg_attrs = fields::cfg_attrs(field);
+            let field_name = fields::member(i, field);
+
+            if fields::is_marker(field) {
+                let value = fields::marker_value(field);
+
+                empty_fields.extend(quote! {
+                    #cfg_attrs
+                    #field_name: #value,
+                });
+
+                continue;
+            }
+
+            let variant = fields::variant_ident(i, field);
+            let value_type = fields::value_type(field, args);
+
+            variants.extend(quote! {
+                #cfg_attrs
+                #variant(#value_type),
+            });
+
+            updates.extend(quote! {
+                #cfg_attrs
+                {
+                    if let Some(value) = self.#field_name {
+                        updates.push(#enum_name::#variant(value));
+                    }
+                }
+            });
+
+            empty_fields.extend(quote! {
+                #cfg_attrs
+                #field_name: None,
+            });
+
+            collected.extend(quote! {
+                #cfg_attrs
+                #enum_name::#variant(value) => opt.#field_name = Some(value),
+            });
+
+            let field_args = fields::args::get(field);
+
+            let apply = if field_args.readonly.is_some() {
+                quote!(#enum_name::#variant(_) => {})
+            } else if fields::is_option(field) && !args.rewrap {
+                let assign = merge::guarded(
+                    field,
+                    quote!(&self.#field_name),
+                    quote!(&value),
+                    quote!(self.#field_name = value;),
+                );
+
+                quote! {
+                    #enum_name::#variant(value) => {
+                        let value = Some(value);
+
+                        #assign
+                    }
+                }
+            } else {
+                let assign = merge::guarded(
+                    field,
+                    quote!(&self.#field_name),
+                    quote!(&value),
+                    quote!(self.#field_name = value;),
+                );
+
+                quote!(#enum_name::#variant(value) => { #assign })
+            };
+
+            applied.extend(quote! {
+                #cfg_attrs
+                #apply
+            });
+        }
+
+        // markers leave parameters only they use out of the variants
+        let mut enum_generics = item.generics.clone();
+        generics::prune(&mut enum_generics, &variants);
+        let (_, ty_generics, enum_where_clause) = enum_generics.split_for_impl();
+
+        quote! {
+            #(#[#enum_attrs])*
+            #vis enum #enum_name #enum_generics #enum_where_clause {
+                #variants
+            }
+
+            impl #impl_generics #opt_name #opt_ty_generics #where_clause {
+                #vis fn into_updates(self) -> Vec<#enum_name #ty_generics> {
+                    let mut updates = Vec::new();
+
+                    #updates
+
+                    updates
+                }
+            }
+
+            impl #impl_generics ::std::iter::FromIterator<#enum_name #ty_generics>
+                for #opt_name #opt_ty_generics #where_clause
+            {
+                fn from_iter<I>(iter: I) -> Self
+                where
+                    I: IntoIterator<Item = #enum_name #ty_generics>,
+                {
+                    let mut opt = #opt_name { #empty_fields };
+
+                    for update in iter {
+                        match update {
+                            #collected
+                        }
+                    }
+
+                    opt
+                }
+            }
+
+            impl #impl_generics #item_name #item_ty_generics #where_clause {
+                #vis fn apply_update(&mut self, update: #enum_name #ty_generics) {
+                    match update {
+                        #applied
+                    }
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
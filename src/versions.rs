@@ -0,0 +1,96 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ItemStruct;
+
+use crate::args::Args;
+use crate::fields;
+
+pub fn generate(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    // without the versions argument, since and until are ignored (see
+    // FieldArgs)
+    if args.versions {
+        let vis = &opt_item.vis;
+
+        let opt_name = &opt_item.ident;
+        let (impl_generics, opt_ty_generics, where_clause) = opt_item.generics.split_for_impl();
+
+        let mut unknown_clears = TokenStream::new();
+        let mut min_versions = TokenStream::new();
+        let mut max_versions = TokenStream::new();
+
+        for (i, field) in item.fields.iter().enumerate() {
+            if fields::is_marker(field) {
+                continue;
+            }
+
+            let field_args = fields::args::get(field);
+
+            let cfg_attrs = fields::cfg_attrs(field);
+            let field_name = fields::member(i, field);
+
+            let unknown = match (field_args.since, field_args.until) {
+                (Some(since), Some(until)) => quote!(!(#since..=#until).contains(&version)),
+                (Some(since), None) => quote!(version < #since),
+                (None, Some(until)) => quote!(version > #until),
+                (None, None) => continue,
+            };
+
+            unknown_clears.extend(quote! {
+                #cfg_attrs
+                {
+                    if #unknown {
+                        self.#field_name = None;
+                    }
+                }
+            });
+
+            if let Some(since) = field_args.since {
+                min_versions.extend(quote! {
+                    #cfg_attrs
+                    {
+                        if self.#field_name.is_some() && min_version < #since {
+                            min_version = #since;
+                        }
+                    }
+                });
+            }
+
+            if let Some(until) = field_args.until {
+                max_versions.extend(quote! {
+                    #cfg_attrs
+                    {
+                        if self.#field_name.is_some() && max_version > #until {
+                            max_version = #until;
+                        }
+                    }
+                });
+            }
+        }
+
+        quote! {
+            impl #impl_generics #opt_name #opt_ty_generics #where_clause {
+                #vis fn for_version(mut self, version: u32) -> Self {
+                    #unknown_clears
+
+                    self
+                }
+
+                #vis fn min_version(&self) -> Option<u32> {
+                    let mut min_version = 0;
+                    let mut max_version = u32::MAX;
+
+                    #min_versions
+                    #max_versions
+
+                    if min_version <= max_version {
+                        Some(min_version)
+                    } else {
+                        None
+                    }
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
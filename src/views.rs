@@ -0,0 +1,150 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_quote, Error, GenericParam, Generics, Ident, ItemStruct};
+
+use crate::args::Args;
+use crate::fields;
+
+pub fn generate(item: &ItemStruct, opt_item: &ItemStruct, args: &Args) -> TokenStream {
+    if args.views {
+        if item.fields.iter().all(fields::is_marker) {
+            return Error::new_spanned(
+                &item.ident,
+                "views require at least one field holding data",
+            )
+            .to_compile_error();
+        }
+
+        let vis = &opt_item.vis;
+
+        let item_name = &item.ident;
+        let (item_impl_generics, item_ty_generics, item_where_clause) =
+            item.generics.split_for_impl();
+
+        let opt_name = &opt_item.ident;
+        let (impl_generics, opt_ty_generics, where_clause) = opt_item.generics.split_for_impl();
+
+        let ref_name = format_ident!("{}Ref", opt_name);
+        let mut_name = format_ident!("{}Mut", opt_name);
+
+        let mut view_generics = opt_item.generics.clone();
+        view_generics
+            .params
+            .insert(0, GenericParam::Lifetime(parse_quote!('view)));
+        let (view_impl_generics, view_ty_generics, _) = view_generics.split_for_impl();
+
+        let ref_struct = view_struct(item, opt_item, &ref_name, &view_generics, args, false);
+        let mut_struct = view_struct(item, opt_item, &mut_name, &view_generics, args, true);
+
+        let mut ref_fields = TokenStream::new();
+        let mut mut_fields = TokenStream::new();
+        let mut selected_fields = TokenStream::new();
+
+        for (i, field) in item.fields.iter().enumerate() {
+            let cfg_attrs = fields::cfg_attrs(field);
+            let field_name = fields::member(i, field);
+            let name = fields::name(i, field);
+
+            if fields::is_marker(field) {
+                let value = fields::marker_value(field);
+
+                for view_fields in [&mut ref_fields, &mut mut_fields, &mut selected_fields] {
+                    view_fields.extend(quote! {
+                        #cfg_attrs
+                        #field_name: #value,
+                    });
+                }
+
+                continue;
+            }
+
+            ref_fields.extend(quote! {
+                #cfg_attrs
+                #field_name: self.#field_name.as_ref(),
+            });
+
+            mut_fields.extend(quote! {
+                #cfg_attrs
+                #field_name: self.#field_name.as_mut(),
+            });
+
+            let value = if fields::is_option(field) && !args.rewrap {
+                quote!(self.#field_name.as_ref())
+            } else {
+                quote!(Some(&self.#field_name))
+            };
+
+            selected_fields.extend(quote! {
+                #cfg_attrs
+                #field_name: if mask.contains(&#name) { #value } else { None },
+            });
+        }
+
+        quote! {
+            #ref_struct
+
+            #mut_struct
+
+            impl #view_impl_generics ::std::clone::Clone for #ref_name #view_ty_generics #where_clause {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            impl #view_impl_generics ::std::marker::Copy for #ref_name #view_ty_generics #where_clause {}
+
+            impl #impl_generics #opt_name #opt_ty_generics #where_clause {
+                #vis fn as_ref<'view>(&'view self) -> #ref_name #view_ty_generics {
+                    #ref_name { #ref_fields }
+                }
+
+                #vis fn as_mut<'view>(&'view mut self) -> #mut_name #view_ty_generics {
+                    #mut_name { #mut_fields }
+                }
+            }
+
+            impl #item_impl_generics #item_name #item_ty_generics #item_where_clause {
+                #vis fn select<'view>(&'view self, mask: &[&str]) -> #ref_name #view_ty_generics {
+                    #ref_name { #selected_fields }
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
+
+/// Struct with the same fields as the original, holding optional references
+/// to their values.
+fn view_struct(
+    item: &ItemStruct,
+    opt_item: &ItemStruct,
+    name: &Ident,
+    generics: &Generics,
+    args: &Args,
+    mutable: bool,
+) -> ItemStruct {
+    let mut view = item.clone();
+
+    view.attrs = Vec::new();
+    view.ident = name.clone();
+    view.vis = opt_item.vis.clone();
+    view.generics = generics.clone();
+
+    for field in view.fields.iter_mut() {
+        let value_type = fields::value_type(field, args);
+
+        field.attrs.retain(fields::is_cfg_attr);
+
+        if fields::is_marker(field) {
+            continue;
+        }
+        field.ty = if mutable {
+            parse_quote!(Option<&'view mut #value_type>)
+        } else {
+            parse_quote!(Option<&'view #value_type>)
+        };
+    }
+
+    view
+}
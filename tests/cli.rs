@@ -0,0 +1,174 @@
+use optfield::optfield;
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[test]
+fn from_args_values_and_flags() {
+    #[optfield(Opt, attrs = (derive(Debug, PartialEq)), from, from_args)]
+    struct Original {
+        name: String,
+        max_conns: u32,
+        #[optfield(long = "db")]
+        database_url: String,
+        verbose: bool,
+        color: bool,
+        ratio: Option<f32>,
+    }
+
+    let (opt, rest) = Opt::from_args(args(&[
+        "input.txt",
+        "--name",
+        "server",
+        "--max-conns=10",
+        "--db",
+        "postgres://localhost",
+        "--verbose",
+        "--no-color",
+        "--unknown",
+        "-x",
+    ]))
+    .unwrap();
+
+    assert_eq!(
+        opt,
+        Opt {
+            name: Some("server".to_string()),
+            max_conns: Some(10),
+            database_url: Some("postgres://localhost".to_string()),
+            verbose: Some(true),
+            color: Some(false),
+            ratio: None,
+        }
+    );
+    assert_eq!(rest, args(&["input.txt", "--unknown", "-x"]));
+
+    let opt = Opt::from(Original {
+        name: String::new(),
+        max_conns: 0,
+        database_url: String::new(),
+        verbose: false,
+        color: true,
+        ratio: Some(0.5),
+    });
+    assert_eq!(opt.ratio, Some(0.5));
+}
+
+#[test]
+fn from_args_stops_at_double_dash() {
+    #[optfield(Opt, from, from_args)]
+    struct Original {
+        count: u8,
+        flag: bool,
+    }
+
+    let (opt, rest) = Opt::from_args(args(&["--count", "1", "--", "--count", "2"])).unwrap();
+
+    assert_eq!(opt.count, Some(1));
+    assert_eq!(opt.flag, None);
+    assert_eq!(rest, args(&["--", "--count", "2"]));
+
+    let (opt, _) = Opt::from_args(args(&["--count=1", "--count=3", "--flag=false"])).unwrap();
+
+    assert_eq!(opt.count, Some(3));
+    assert_eq!(opt.flag, Some(false));
+
+    let opt = Opt::from(Original {
+        count: 0,
+        flag: true,
+    });
+    assert_eq!(opt.flag, Some(true));
+}
+
+#[test]
+fn from_args_errors() {
+    #[optfield(Opt, attrs = (derive(Debug)), from, from_args)]
+    struct Original {
+        count: u8,
+        flag: bool,
+    }
+
+    assert_eq!(
+        Opt::from_args(args(&["--count"])).unwrap_err(),
+        OptArgsError::MissingValue("--count".to_string())
+    );
+
+    let error = Opt::from_args(args(&["--count", "many"])).unwrap_err();
+    assert_eq!(
+        error,
+        OptArgsError::InvalidValue {
+            arg: "--count".to_string(),
+            message: "invalid digit found in string".to_string(),
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "invalid value for `--count`: invalid digit found in string"
+    );
+
+    assert_eq!(
+        Opt::from_args(args(&["--no-flag=true"])).unwrap_err(),
+        OptArgsError::ValueOnNegatedFlag("--no-flag".to_string())
+    );
+
+    let opt = Opt::from(Original {
+        count: 0,
+        flag: true,
+    });
+    assert_eq!(opt.count, Some(0));
+}
+
+#[test]
+fn from_args_rewrap() {
+    #[optfield(Opt, rewrap, from, from_args)]
+    struct Original {
+        level: Option<u8>,
+        quiet: Option<bool>,
+    }
+
+    let (opt, _) = Opt::from_args(args(&["--level", "3", "--no-quiet"])).unwrap();
+
+    assert_eq!(opt.level, Some(Some(3)));
+    assert_eq!(opt.quiet, Some(Some(false)));
+
+    let opt = Opt::from(Original {
+        level: None,
+        quiet: None,
+    });
+    assert_eq!(opt.level, Some(None));
+}
+
+#[test]
+fn from_args_tuple_struct() {
+    #[optfield(Opt, from, from_args)]
+    struct Original(u8, #[optfield(long = "second")] String);
+
+    let (opt, _) = Opt::from_args(args(&["--0", "1", "--second", "test"])).unwrap();
+
+    assert_eq!(opt.0, Some(1));
+    assert_eq!(opt.1, Some("test".to_string()));
+
+    let opt = Opt::from(Original(0, String::new()));
+    assert_eq!(opt.0, Some(0));
+}
+
+#[test]
+fn from_args_cfg_field() {
+    #![allow(unexpected_cfgs)]
+
+    #[optfield(Opt, field_attrs, from, from_args)]
+    struct Original {
+        #[cfg(some_feature)]
+        feature_field: bool,
+        field: i32,
+    }
+
+    let (opt, rest) = Opt::from_args(args(&["--feature-field", "--field", "1"])).unwrap();
+
+    assert_eq!(opt.field, Some(1));
+    assert_eq!(rest, args(&["--feature-field"]));
+
+    let opt = Opt::from(Original { field: 0 });
+    assert_eq!(opt.field, Some(0));
+}
@@ -0,0 +1,92 @@
+use optfield::optfield;
+
+#[test]
+fn debug_only_set_fields() {
+    #[optfield(Opt, from, debug)]
+    struct Original {
+        name: String,
+        age: u32,
+        email: Option<String>,
+        #[optfield(sensitive)]
+        password: String,
+    }
+
+    let opt = Opt {
+        name: Some("test".to_string()),
+        age: None,
+        email: Some("test@example.com".to_string()),
+        password: Some("secret".to_string()),
+    };
+
+    assert_eq!(
+        format!("{:?}", opt),
+        r#"Opt { name: "test", email: "test@example.com", password: *** }"#
+    );
+
+    let opt = Opt::from(Original {
+        name: "test".to_string(),
+        age: 30,
+        email: None,
+        password: "secret".to_string(),
+    });
+
+    assert_eq!(
+        format!("{:?}", opt),
+        r#"Opt { name: "test", age: 30, password: *** }"#
+    );
+}
+
+#[test]
+fn debug_empty() {
+    #[optfield(Opt, from, debug)]
+    struct Original {
+        name: String,
+    }
+
+    assert_eq!(format!("{:?}", Opt { name: None }), "Opt");
+
+    let opt = Opt::from(Original {
+        name: "test".to_string(),
+    });
+    assert_eq!(format!("{:#?}", opt), "Opt {\n    name: \"test\",\n}");
+}
+
+#[test]
+fn debug_rewrap() {
+    #[optfield(Opt, rewrap, from, debug)]
+    struct Original {
+        email: Option<String>,
+    }
+
+    let opt = Opt::from(Original { email: None });
+
+    assert_eq!(format!("{:?}", opt), "Opt { email: None }");
+}
+
+#[test]
+fn debug_generic_tuple_struct() {
+    struct NotDebug;
+
+    #[optfield(Opt, from, debug)]
+    struct Original<T, S>(T, #[optfield(sensitive)] S);
+
+    let opt = Opt::from(Original(1, NotDebug));
+
+    assert_eq!(format!("{:?}", opt), "Opt { 0: 1, 1: *** }");
+}
+
+#[test]
+fn debug_cfg_field() {
+    #![allow(unexpected_cfgs)]
+
+    #[optfield(Opt, field_attrs, from, debug)]
+    struct Original {
+        #[cfg(some_feature)]
+        feature_field: String,
+        field: i32,
+    }
+
+    let opt = Opt::from(Original { field: 1 });
+
+    assert_eq!(format!("{:?}", opt), "Opt { field: 1 }");
+}
@@ -0,0 +1,92 @@
+use optfield::optfield;
+
+#[test]
+fn default_without_bounds() {
+    struct NotDefault;
+
+    #[optfield(Opt, from, default)]
+    struct Original<T> {
+        value: T,
+        name: String,
+    }
+
+    let opt: Opt<NotDefault> = Opt::default();
+
+    assert!(opt.value.is_none());
+    assert!(opt.name.is_none());
+
+    let opt = Opt::from(Original {
+        value: NotDefault,
+        name: "test".to_string(),
+    });
+    assert!(opt.value.is_some());
+}
+
+#[optfield(StaticOpt, from, default)]
+struct StaticOriginal {
+    name: String,
+    count: u32,
+}
+
+static EMPTY: StaticOpt = StaticOpt::EMPTY;
+const NONE: StaticOpt = StaticOpt::none();
+
+#[test]
+fn default_in_static_and_const() {
+    assert_eq!(EMPTY.name, None);
+    assert_eq!(NONE.count, None);
+
+    let opt = StaticOpt::from(StaticOriginal {
+        name: "test".to_string(),
+        count: 1,
+    });
+    assert_eq!(opt.count, Some(1));
+}
+
+#[test]
+fn default_tuple_lifetime_struct() {
+    #[optfield(Opt, from, default)]
+    struct Original<'a, T>(&'a T, u8);
+
+    let opt = Opt::<u8>::EMPTY;
+
+    assert_eq!(opt.0, None);
+    assert_eq!(opt.1, None);
+
+    let value = 1;
+    let opt = Opt::from(Original(&value, 2));
+    assert_eq!(opt.0, Some(&1));
+}
+
+#[test]
+fn default_cfg_field() {
+    #![allow(unexpected_cfgs)]
+
+    #[optfield(Opt, field_attrs, from, default)]
+    struct Original {
+        #[cfg(some_feature)]
+        feature_field: String,
+        field: i32,
+    }
+
+    assert_eq!(Opt::default().field, None);
+
+    let opt = Opt::from(Original { field: 1 });
+    assert_eq!(opt.field, Some(1));
+}
+
+#[test]
+fn default_bounded_generics() {
+    #[optfield(Opt, default)]
+    struct Original<T: Clone>
+    where
+        T: PartialEq,
+    {
+        value: T,
+    }
+
+    let opt = Opt::<String>::EMPTY;
+    assert_eq!(opt.value, None);
+
+    let _ = Original { value: 1 }.value;
+}
@@ -0,0 +1,152 @@
+use optfield::optfield;
+
+fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+    vars.iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn from_env_iter_prefix() {
+    #[optfield(Opt, attrs = (derive(Debug, PartialEq)), from, from_env)]
+    struct Original {
+        name: String,
+        max_conns: u32,
+        #[optfield(env = "DATABASE_URL")]
+        database_url: String,
+        ratio: Option<f32>,
+    }
+
+    let opt = Opt::from_env_iter(
+        "APP",
+        vars(&[
+            ("APP_NAME", "server"),
+            ("APP_MAX_CONNS", "10"),
+            ("APP_DATABASE_URL", "ignored"),
+            ("DATABASE_URL", "postgres://localhost"),
+            ("OTHER", "value"),
+        ]),
+    )
+    .unwrap();
+
+    assert_eq!(
+        opt,
+        Opt {
+            name: Some("server".to_string()),
+            max_conns: Some(10),
+            database_url: Some("postgres://localhost".to_string()),
+            ratio: None,
+        }
+    );
+
+    let opt = Opt::from(Original {
+        name: "server".to_string(),
+        max_conns: 0,
+        database_url: String::new(),
+        ratio: Some(0.5),
+    });
+    assert_eq!(opt.ratio, Some(0.5));
+}
+
+#[test]
+fn from_env_iter_no_prefix() {
+    #[optfield(Opt, from, from_env)]
+    struct Original {
+        max_conns: u32,
+    }
+
+    let opt = Opt::from_env_iter("", vars(&[("MAX_CONNS", "1")])).unwrap();
+    assert_eq!(opt.max_conns, Some(1));
+
+    let opt = Opt::from(Original { max_conns: 0 });
+    assert_eq!(opt.max_conns, Some(0));
+}
+
+#[test]
+fn from_env_iter_invalid_value() {
+    #[optfield(Opt, attrs = (derive(Debug)), from, from_env)]
+    struct Original {
+        max_conns: u32,
+    }
+
+    let error = Opt::from_env_iter("APP", vars(&[("APP_MAX_CONNS", "many")])).unwrap_err();
+
+    assert_eq!(
+        error,
+        OptEnvError::InvalidValue {
+            var: "APP_MAX_CONNS".to_string(),
+            message: "invalid digit found in string".to_string(),
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "invalid value for `APP_MAX_CONNS`: invalid digit found in string"
+    );
+
+    let opt = Opt::from(Original { max_conns: 0 });
+    assert_eq!(opt.max_conns, Some(0));
+}
+
+#[test]
+fn from_env_process() {
+    #[optfield(Opt, rewrap, from, from_env)]
+    struct Original {
+        level: Option<u8>,
+        unset: String,
+    }
+
+    std::env::set_var("OPTFIELD_FROM_ENV_TEST_LEVEL", "3");
+
+    let opt = Opt::from_env("OPTFIELD_FROM_ENV_TEST").unwrap();
+
+    assert_eq!(opt.level, Some(Some(3)));
+    assert_eq!(opt.unset, None);
+
+    let opt = Opt::from(Original {
+        level: None,
+        unset: String::new(),
+    });
+    assert_eq!(opt.level, Some(None));
+}
+
+#[test]
+fn from_env_tuple_struct() {
+    #[optfield(Opt, from, from_env)]
+    struct Original(u8, #[optfield(env = "SECOND")] bool);
+
+    let opt = Opt::from_env_iter("APP", vars(&[("APP_0", "1"), ("SECOND", "true")])).unwrap();
+
+    assert_eq!(opt.0, Some(1));
+    assert_eq!(opt.1, Some(true));
+
+    let opt = Opt::from(Original(0, false));
+    assert_eq!(opt.1, Some(false));
+}
+
+#[test]
+fn from_env_cfg_field() {
+    #![allow(unexpected_cfgs)]
+
+    #[optfield(Opt, field_attrs, from, from_env)]
+    struct Original {
+        #[cfg(some_feature)]
+        feature_field: String,
+        field: i32,
+    }
+
+    let opt = Opt::from_env_iter("APP", vars(&[("APP_FIELD", "1")])).unwrap();
+    assert_eq!(opt.field, Some(1));
+
+    let opt = Opt::from(Original { field: 0 });
+    assert_eq!(opt.field, Some(0));
+}
+
+#[test]
+fn from_env_unit_struct() {
+    #[optfield(Opt, from, from_env)]
+    struct Original;
+
+    assert!(Opt::from_env_iter("APP", vars(&[("APP_FIELD", "1")])).is_ok());
+
+    let _ = Opt::from(Original);
+}
@@ -0,0 +1,139 @@
+use optfield::optfield;
+
+#[test]
+fn field_enum_named_struct() {
+    #[optfield(Opt, field_enum, from)]
+    struct Original {
+        name: String,
+        count: u32,
+        r#type: Option<bool>,
+    }
+
+    assert_eq!(
+        OptField::FIELDS,
+        &[OptField::Name, OptField::Count, OptField::Type]
+    );
+
+    let names: Vec<_> = OptField::FIELDS.iter().map(|field| field.name()).collect();
+    assert_eq!(names, vec!["name", "count", "type"]);
+
+    let opt = Opt {
+        name: None,
+        count: Some(1),
+        r#type: Some(true),
+    };
+
+    assert!(!opt.is_set(OptField::Name));
+    assert!(opt.is_set(OptField::Count));
+    assert_eq!(
+        opt.set_fields().collect::<Vec<_>>(),
+        vec![OptField::Count, OptField::Type]
+    );
+    assert_eq!(opt.len(), 2);
+    assert!(!opt.is_empty());
+    assert!(!opt.is_complete());
+
+    let full = Opt::from(Original {
+        name: "test".to_string(),
+        count: 1,
+        r#type: None,
+    });
+
+    assert!(!full.is_set(OptField::Type));
+    assert_eq!(full.len(), 2);
+
+    let empty = Opt {
+        name: None,
+        count: None,
+        r#type: None,
+    };
+
+    assert!(empty.is_empty());
+    assert_eq!(empty.len(), 0);
+}
+
+#[test]
+fn field_enum_rewrap_complete() {
+    #[optfield(Opt, field_enum, rewrap, from)]
+    struct Original {
+        field: Option<u8>,
+    }
+
+    let opt = Opt::from(Original { field: None });
+
+    assert!(opt.is_set(OptField::Field));
+    assert!(opt.is_complete());
+}
+
+#[test]
+fn field_enum_tuple_struct() {
+    #[optfield(Opt, field_enum, from)]
+    struct Original(u8, String);
+
+    assert_eq!(OptField::FIELDS, &[OptField::_0, OptField::_1]);
+    assert_eq!(OptField::_1.name(), "_1");
+
+    let opt = Opt(None, Some("test".to_string()));
+    assert_eq!(opt.set_fields().collect::<Vec<_>>(), vec![OptField::_1]);
+
+    let opt = Opt::from(Original(1, "test".to_string()));
+    assert!(opt.is_complete());
+}
+
+#[test]
+fn field_enum_generic_struct() {
+    #[optfield(Opt, field_enum, from)]
+    struct Original<T> {
+        value: T,
+    }
+
+    let opt = Opt::from(Original { value: 1u8 });
+
+    assert!(opt.is_set(OptField::Value));
+    assert!(opt.is_complete());
+}
+
+#[test]
+fn field_enum_cfg_field() {
+    #![allow(unexpected_cfgs)]
+
+    #[optfield(Opt, field_attrs, field_enum, from)]
+    struct Original {
+        #[cfg(some_feature)]
+        feature_field: String,
+        field: i32,
+    }
+
+    assert_eq!(OptField::FIELDS, &[OptField::Field]);
+
+    let opt = Opt::from(Original { field: 1 });
+    assert!(opt.is_complete());
+}
+
+#[test]
+fn field_enum_unit_struct() {
+    #[optfield(Opt, field_enum, from)]
+    struct Original;
+
+    let opt = Opt::from(Original);
+
+    assert!(OptField::FIELDS.is_empty());
+    assert!(opt.is_empty());
+    assert!(opt.is_complete());
+}
+
+#[test]
+fn field_enum_underscore_names() {
+    #[optfield(Opt, field_enum, from)]
+    struct Original {
+        _1: i32,
+        __: i32,
+    }
+
+    assert_eq!(OptField::FIELDS, &[OptField::Field1, OptField::Field]);
+    assert_eq!(OptField::Field1.name(), "_1");
+    assert_eq!(OptField::Field.name(), "__");
+
+    let opt = Opt::from(Original { _1: 1, __: 2 });
+    assert!(opt.is_complete());
+}
@@ -0,0 +1,220 @@
+use optfield::optfield;
+
+#[test]
+fn field_mask_round_trip() {
+    #[optfield(Opt, attrs = (derive(Debug, PartialEq)), field_mask)]
+    #[derive(Clone)]
+    struct Original {
+        name: String,
+        age: u32,
+        email: Option<String>,
+    }
+
+    let source = Original {
+        name: "test".to_string(),
+        age: 30,
+        email: Some("test@example.com".to_string()),
+    };
+
+    let opt = Opt::from_field_mask(&["name", "email"], &source).unwrap();
+
+    assert_eq!(
+        opt,
+        Opt {
+            name: Some("test".to_string()),
+            age: None,
+            email: Some("test@example.com".to_string()),
+        }
+    );
+    assert_eq!(opt.field_mask(), vec!["name", "email"]);
+
+    let opt = Opt::from_field_mask(&[], &source).unwrap();
+    assert!(opt.field_mask().is_empty());
+}
+
+#[test]
+fn field_mask_errors() {
+    #[optfield(Opt, attrs = (derive(Debug)), field_mask)]
+    struct Original {
+        name: String,
+    }
+
+    let source = Original {
+        name: "test".to_string(),
+    };
+
+    let error = Opt::from_field_mask(&["unknown"], &source).unwrap_err();
+    assert_eq!(error, OptMaskError::UnknownField("unknown".to_string()));
+    assert_eq!(error.to_string(), "unknown field `unknown`");
+
+    let error = Opt::from_field_mask(&["name.first"], &source).unwrap_err();
+    assert_eq!(error, OptMaskError::NestedPath("name.first".to_string()));
+    assert_eq!(
+        error.to_string(),
+        "nested path `name.first` is not supported"
+    );
+
+    assert_eq!(
+        Opt::from_field_mask(&["other.first"], &source).unwrap_err(),
+        OptMaskError::UnknownField("other.first".to_string())
+    );
+}
+
+#[test]
+fn field_mask_rewrap() {
+    #[optfield(Opt, rewrap, field_mask)]
+    struct Original {
+        email: Option<String>,
+    }
+
+    let opt = Opt::from_field_mask(&["email"], &Original { email: None }).unwrap();
+
+    assert_eq!(opt.email, Some(None));
+    assert_eq!(opt.field_mask(), vec!["email"]);
+}
+
+#[test]
+fn field_mask_tuple_generic_struct() {
+    #[optfield(Opt, field_mask)]
+    struct Original<T>(T, String);
+
+    let opt = Opt::from_field_mask(&["1"], &Original(1, "test".to_string())).unwrap();
+
+    assert_eq!(opt.0, None);
+    assert_eq!(opt.1, Some("test".to_string()));
+    assert_eq!(opt.field_mask(), vec!["1"]);
+}
+
+#[test]
+fn field_mask_cfg_field() {
+    #![allow(unexpected_cfgs)]
+
+    #[optfield(Opt, field_attrs, field_mask)]
+    struct Original {
+        #[cfg(some_feature)]
+        feature_field: String,
+        field: i32,
+    }
+
+    let source = Original { field: 1 };
+
+    assert!(Opt::from_field_mask(&["feature_field"], &source).is_err());
+
+    let opt = Opt::from_field_mask(&["field"], &source).unwrap();
+    assert_eq!(opt.field_mask(), vec!["field"]);
+}
+
+#[test]
+fn field_mask_nested() {
+    #[optfield(AddressOpt, attrs = (derive(Debug, PartialEq)), from, field_mask)]
+    #[derive(Clone)]
+    struct Address {
+        city: String,
+        street: String,
+    }
+
+    #[optfield(Opt, attrs = (derive(Debug, PartialEq)), from, field_mask)]
+    #[derive(Clone)]
+    struct Original {
+        name: String,
+        #[optfield(nested = AddressOpt)]
+        address: Address,
+    }
+
+    let source = Original {
+        name: "test".to_string(),
+        address: Address {
+            city: "city".to_string(),
+            street: "street".to_string(),
+        },
+    };
+
+    let opt = Opt::from_field_mask(&["address.city"], &source).unwrap();
+
+    assert_eq!(
+        opt,
+        Opt {
+            name: None,
+            address: Some(AddressOpt {
+                city: Some("city".to_string()),
+                street: None,
+            }),
+        }
+    );
+    assert_eq!(opt.field_mask(), vec!["address.city"]);
+
+    let opt = Opt::from_field_mask(&["address", "address.city"], &source).unwrap();
+    assert_eq!(opt.field_mask(), vec!["address.city", "address.street"]);
+
+    assert_eq!(
+        Opt::from_field_mask(&["address.unknown"], &source).unwrap_err(),
+        OptMaskError::UnknownField("address.unknown".to_string())
+    );
+    assert_eq!(
+        Opt::from_field_mask(&["address.city.first"], &source).unwrap_err(),
+        OptMaskError::NestedPath("address.city.first".to_string())
+    );
+
+    let opt = Opt::from(source);
+    assert_eq!(opt.name, Some("test".to_string()));
+    assert_eq!(opt.address.unwrap().street, Some("street".to_string()));
+}
+
+#[test]
+fn field_mask_nested_generic_tuple_struct() {
+    mod inner {
+        use optfield::optfield;
+
+        #[optfield(pub Opt, from, field_mask)]
+        #[derive(Clone)]
+        pub struct Inner<T>(pub T, pub u8);
+    }
+
+    #[optfield(Opt, field_mask)]
+    struct Original<T: Clone>(#[optfield(nested = inner::Opt<T>)] inner::Inner<T>);
+
+    let source = Original(inner::Inner("test", 1));
+
+    let opt = Opt::from_field_mask(&["0.1"], &source).unwrap();
+    assert_eq!(opt.0.as_ref().unwrap().1, Some(1));
+    assert_eq!(opt.field_mask(), vec!["0.1"]);
+
+    assert_eq!(
+        Opt::from_field_mask(&["0.2"], &source).err(),
+        Some(OptMaskError::UnknownField("0.2".to_string()))
+    );
+}
+
+#[test]
+fn field_mask_nested_multiple_attributes() {
+    #[optfield(AddressOpt, from, field_mask)]
+    #[derive(Clone)]
+    struct Address {
+        city: String,
+    }
+
+    #[optfield(Opt, field_mask)]
+    #[optfield(Patch, from, merge_fn)]
+    struct Original {
+        #[optfield(nested = AddressOpt)]
+        address: Address,
+    }
+
+    let mut original = Original {
+        address: Address {
+            city: "city".to_string(),
+        },
+    };
+
+    original.merge_opt(Patch {
+        address: Some(Address {
+            city: "other".to_string(),
+        }),
+    });
+
+    let opt = Opt::from_field_mask(&["address.city"], &original).unwrap();
+    assert_eq!(opt.address.unwrap().city, Some("other".to_string()));
+
+    let patch = Patch::from(original);
+    assert_eq!(patch.address.unwrap().city, "other");
+}
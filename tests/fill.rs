@@ -0,0 +1,90 @@
+use optfield::optfield;
+
+#[test]
+fn fill_struct() {
+    #[optfield(Opt, attrs, fill_fn)]
+    #[optfield(OptRewrap, attrs, rewrap, fill_fn = fill_rewrap)]
+    #[derive(Clone, Debug, PartialEq)]
+    struct Original<'a, T> {
+        number: u32,
+        #[optfield(fill_default)]
+        text: &'a str,
+        generic: T,
+        optional: Option<&'a [u8]>,
+    }
+
+    let original = Original {
+        number: 12,
+        text: "",
+        generic: "testing".to_string(),
+        optional: None,
+    };
+
+    let opt = Opt {
+        number: Some(1),
+        text: Some("filled"),
+        generic: Some("overwritten".to_string()),
+        optional: Some(&[1, 2]),
+    };
+
+    let mut filled = original.clone();
+    filled.fill_opt(opt.clone());
+
+    // fields that are not considered unset are never overwritten
+    assert_eq!(filled.number, original.number);
+    assert_eq!(filled.generic, original.generic);
+    assert_eq!(filled.text, "filled");
+    assert_eq!(filled.optional, Some(&[1, 2][..]));
+
+    // filled fields are set now, so nothing changes
+    let mut filled_again = filled.clone();
+    filled_again.fill_opt(Opt {
+        text: Some("other"),
+        optional: Some(&[3]),
+        ..opt
+    });
+
+    assert_eq!(filled_again, filled);
+
+    let mut filled_rewrap = original.clone();
+    filled_rewrap.fill_rewrap(OptRewrap {
+        number: None,
+        text: None,
+        generic: None,
+        optional: Some(Some(&[4])),
+    });
+
+    assert_eq!(filled_rewrap.optional, Some(&[4][..]));
+    assert_eq!(filled_rewrap.text, original.text);
+}
+
+#[test]
+fn fill_tuple_struct() {
+    #[optfield(Opt, attrs, fill_fn)]
+    #[derive(Clone, Debug, PartialEq)]
+    struct Original(Option<i32>, #[optfield(fill_default)] String);
+
+    let mut original = Original(None, "set".to_string());
+
+    original.fill_opt(Opt(Some(1), Some("other".to_string())));
+
+    assert_eq!(original, Original(Some(1), "set".to_string()));
+}
+
+#[test]
+fn fill_cfg_field() {
+    #![allow(unexpected_cfgs)]
+
+    #[optfield(Opt, field_attrs, fill_fn)]
+    #[derive(Clone, Debug)]
+    struct Original {
+        #[cfg(some_feature)]
+        #[optfield(fill_default)]
+        feature_field: String,
+        field: Option<i32>,
+    }
+
+    let mut original = Original { field: None };
+    original.fill_opt(Opt { field: Some(1) });
+    assert_eq!(original.field, Some(1));
+}
@@ -67,3 +67,69 @@ fn from_cfg_field() {
     let opt = Opt::from(original.clone());
     assert_eq!(original.field, opt.field.unwrap());
 }
+
+#[test]
+fn from_ref_struct() {
+    #[optfield(Opt, attrs, from = ref)]
+    #[optfield(OptRewrap, attrs, rewrap, from = (owned, ref))]
+    #[derive(Clone, Debug, PartialEq)]
+    struct Original<'a, T> {
+        number: u32,
+        text: &'a str,
+        generic: T,
+        optional: Option<Vec<u8>>,
+    }
+
+    let original = Original {
+        number: 12,
+        text: "test",
+        generic: "testing".to_string(),
+        optional: Some(vec![1, 2, 3]),
+    };
+
+    let opt = Opt::from(&original);
+    assert_eq!(original.number, opt.number.unwrap());
+    assert_eq!(original.text, opt.text.unwrap());
+    assert_eq!(original.generic, opt.generic.unwrap());
+    assert_eq!(original.optional, opt.optional);
+
+    let opt_rewrap = OptRewrap::from(&original);
+    assert_eq!(original.optional, opt_rewrap.optional.unwrap());
+
+    let opt_rewrap = OptRewrap::from(original.clone());
+    assert_eq!(original.generic, opt_rewrap.generic.unwrap());
+}
+
+#[test]
+fn from_ref_bounded_tuple_struct() {
+    #[optfield(Opt, attrs, from = (owned, ref))]
+    #[derive(Debug)]
+    struct Original<T: Default>(i32, Option<T>)
+    where
+        T: PartialEq;
+
+    let original = Original(21, Some(1));
+
+    let opt = Opt::from(&original);
+    assert_eq!(original.0, opt.0.unwrap());
+    assert_eq!(original.1, opt.1);
+
+    let opt = Opt::from(original);
+    assert_eq!(opt.1, Some(1));
+}
+
+#[test]
+fn from_ref_cfg_field() {
+    #![allow(unexpected_cfgs)]
+
+    #[optfield(Opt, field_attrs, from = ref)]
+    struct Original {
+        #[cfg(some_feature)]
+        feature_field: String,
+        field: i32,
+    }
+
+    let original = Original { field: 1 };
+    let opt = Opt::from(&original);
+    assert_eq!(original.field, opt.field.unwrap());
+}
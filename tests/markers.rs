@@ -0,0 +1,128 @@
+use std::marker::PhantomData;
+
+use optfield::optfield;
+
+#[test]
+fn markers_merge_and_from() {
+    #[derive(Debug, PartialEq)]
+    struct User;
+
+    #[optfield(Opt, from, merge_fn, default)]
+    #[derive(Debug, PartialEq)]
+    struct Id<T> {
+        value: u64,
+        marker: PhantomData<T>,
+        unit: (),
+    }
+
+    let opt: Opt<User> = Opt::from(Id {
+        value: 1,
+        marker: PhantomData,
+        unit: (),
+    });
+
+    let marker: PhantomData<User> = opt.marker;
+    let unit: () = opt.unit;
+    assert_eq!(opt.value, Some(1));
+
+    let mut id = Id {
+        value: 2,
+        marker,
+        unit,
+    };
+
+    id.merge_opt(opt);
+    assert_eq!(id.value, 1);
+
+    id.merge_opt(Opt::default());
+    assert_eq!(id.value, 1);
+}
+
+#[test]
+fn markers_tuple_struct() {
+    #[optfield(Opt, from, merge_fn, field_enum)]
+    struct Id<T>(u64, PhantomData<fn() -> T>);
+
+    let opt: Opt<String> = Opt::from(Id(1, PhantomData));
+    assert_eq!(opt.0, Some(1));
+    assert_eq!(OptField::FIELDS, &[OptField::_0]);
+    assert!(opt.is_complete());
+
+    let mut id = Id(2, PhantomData);
+    id.merge_opt(opt);
+    assert_eq!(id.0, 1);
+}
+
+#[test]
+fn markers_three_way_and_views() {
+    #[optfield(
+        Opt,
+        from,
+        three_way,
+        views,
+        update_enum = (derive(Debug, PartialEq)),
+        debug
+    )]
+    #[derive(Debug, PartialEq)]
+    struct Id<T> {
+        value: u64,
+        marker: PhantomData<T>,
+    }
+
+    let base = Id::<u8> {
+        value: 1,
+        marker: PhantomData,
+    };
+
+    let ours = Opt::from(Id {
+        value: 2,
+        marker: PhantomData,
+    });
+
+    let theirs = Opt {
+        value: None,
+        marker: PhantomData,
+    };
+
+    let mut merged = Opt::three_way(&base, ours, theirs).unwrap();
+    assert_eq!(merged.value, Some(2));
+    assert_eq!(format!("{:?}", merged), "Opt { value: 2 }");
+
+    assert_eq!(merged.as_ref().value, Some(&2));
+    *merged.as_mut().value.unwrap() = 3;
+    assert_eq!(base.select(&["value"]).value, Some(&1));
+
+    assert_eq!(merged.into_updates(), vec![IdUpdate::Value(3)]);
+}
+
+#[test]
+fn markers_string_keyed() {
+    #[optfield(Opt, from, query, str_access, field_mask, matches)]
+    struct Id<T> {
+        value: u64,
+        marker: PhantomData<T>,
+    }
+
+    let mut opt: Opt<()> = Opt::from_query("value=1").unwrap();
+    assert_eq!(opt.value, Some(1));
+    assert_eq!(opt.to_query(), "value=1");
+
+    assert!(opt.set_str("marker", "").is_err());
+    opt.set_str("value", "2").unwrap();
+
+    assert_eq!(opt.field_mask(), vec!["value"]);
+
+    let id = Id {
+        value: 2,
+        marker: PhantomData,
+    };
+
+    assert!(opt.matches(&id));
+
+    let opt = Opt::from_field_mask(&["value"], &id).unwrap();
+    assert_eq!(opt.value, Some(2));
+    assert!(Opt::from_field_mask(&["marker"], &id).is_err());
+
+    let opt = Opt::from(id);
+    assert_eq!(opt.value, Some(2));
+}
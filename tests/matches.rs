@@ -0,0 +1,238 @@
+use optfield::optfield;
+
+#[test]
+fn matches_equality() {
+    #[optfield(Opt, matches)]
+    #[derive(Debug, PartialEq)]
+    struct Original {
+        name: String,
+        age: u32,
+        email: Option<String>,
+    }
+
+    let items = vec![
+        Original {
+            name: "first".to_string(),
+            age: 30,
+            email: None,
+        },
+        Original {
+            name: "second".to_string(),
+            age: 30,
+            email: Some("second@example.com".to_string()),
+        },
+        Original {
+            name: "third".to_string(),
+            age: 40,
+            email: Some("third@example.com".to_string()),
+        },
+    ];
+
+    let empty = Opt {
+        name: None,
+        age: None,
+        email: None,
+    };
+
+    assert!(items.iter().all(|item| empty.matches(item)));
+
+    let by_age = Opt {
+        name: None,
+        age: Some(30),
+        email: None,
+    };
+
+    assert_eq!(
+        by_age.filter(&items).collect::<Vec<_>>(),
+        vec![&items[0], &items[1]]
+    );
+
+    let by_email = Opt {
+        name: None,
+        age: Some(30),
+        email: Some("second@example.com".to_string()),
+    };
+
+    assert_eq!(
+        by_email.filter(items.iter()).collect::<Vec<_>>(),
+        vec![&items[1]]
+    );
+}
+
+#[test]
+fn matches_modes() {
+    #[optfield(Opt, matches)]
+    struct Original {
+        #[optfield(match = contains)]
+        name: String,
+        #[optfield(match = prefix)]
+        email: Option<String>,
+        #[optfield(match = min)]
+        age: u32,
+        #[optfield(match = max)]
+        score: f64,
+    }
+
+    let original = Original {
+        name: "optfield".to_string(),
+        email: Some("test@example.com".to_string()),
+        age: 30,
+        score: 0.5,
+    };
+
+    let opt = Opt {
+        name: Some("field".to_string()),
+        email: Some("test@".to_string()),
+        age: Some(30),
+        score: Some(0.5),
+    };
+
+    assert!(opt.matches(&original));
+
+    let cases = vec![
+        Opt {
+            name: Some("other".to_string()),
+            email: None,
+            age: None,
+            score: None,
+        },
+        Opt {
+            name: None,
+            email: Some("example".to_string()),
+            age: None,
+            score: None,
+        },
+        Opt {
+            name: None,
+            email: None,
+            age: Some(31),
+            score: None,
+        },
+        Opt {
+            name: None,
+            email: None,
+            age: None,
+            score: Some(0.4),
+        },
+    ];
+
+    for opt in cases {
+        assert!(!opt.matches(&original));
+    }
+
+    let no_email = Original {
+        email: None,
+        ..original
+    };
+
+    assert!(!Opt {
+        name: None,
+        email: Some(String::new()),
+        age: None,
+        score: None,
+    }
+    .matches(&no_email));
+}
+
+#[test]
+fn matches_rewrap() {
+    #[optfield(Opt, rewrap, matches)]
+    struct Original {
+        email: Option<String>,
+    }
+
+    let original = Original { email: None };
+
+    assert!(Opt { email: Some(None) }.matches(&original));
+    assert!(!Opt {
+        email: Some(Some("test".to_string()))
+    }
+    .matches(&original));
+}
+
+#[test]
+fn matches_tuple_generic_struct() {
+    #[optfield(Opt, matches)]
+    struct Original<'a, T>(&'a str, T);
+
+    let items = vec![Original("first", 1), Original("second", 2)];
+
+    let opt = Opt(None, Some(2));
+    let matched: Vec<_> = opt.filter(&items).map(|item| item.0).collect();
+
+    assert_eq!(matched, vec!["second"]);
+}
+
+#[test]
+fn matches_cfg_field() {
+    #![allow(unexpected_cfgs)]
+
+    #[optfield(Opt, field_attrs, matches)]
+    struct Original {
+        #[cfg(some_feature)]
+        feature_field: String,
+        field: i32,
+    }
+
+    assert!(Opt { field: Some(1) }.matches(&Original { field: 1 }));
+    assert!(!Opt { field: Some(2) }.matches(&Original { field: 1 }));
+}
+
+#[test]
+fn matches_range() {
+    #[optfield(Opt, matches)]
+    struct Original<T> {
+        #[optfield(match = range)]
+        age: u32,
+        #[optfield(match = range)]
+        score: Option<T>,
+    }
+
+    let items = vec![
+        Original {
+            age: 20,
+            score: Some(0.5),
+        },
+        Original {
+            age: 30,
+            score: None,
+        },
+        Original {
+            age: 40,
+            score: Some(0.9),
+        },
+    ];
+
+    let opt = Opt {
+        age: Some(25..=40),
+        score: None,
+    };
+
+    let ages: Vec<_> = opt.filter(&items).map(|item| item.age).collect();
+    assert_eq!(ages, vec![30, 40]);
+
+    let opt = Opt {
+        age: None,
+        score: Some(0.0..=0.6),
+    };
+
+    let ages: Vec<_> = opt.filter(&items).map(|item| item.age).collect();
+    assert_eq!(ages, vec![20]);
+}
+
+#[test]
+fn matches_range_multiple_attributes() {
+    #[optfield(Filter, matches)]
+    #[optfield(Patch, from, merge_fn)]
+    struct Original {
+        #[optfield(match = range)]
+        age: u32,
+    }
+
+    let mut original = Original { age: 20 };
+
+    original.merge_opt(Patch { age: Some(30) });
+
+    assert!(Filter { age: Some(25..=35) }.matches(&original));
+    assert_eq!(Patch::from(original).age, Some(30));
+}
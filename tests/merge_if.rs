@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use optfield::optfield;
+
+fn newer(current: &u32, new: &u32) -> bool {
+    new > current
+}
+
+fn raise_only(current: &Option<u32>, new: &Option<u32>) -> bool {
+    match (current, new) {
+        (Some(current), Some(new)) => new > current,
+        _ => true,
+    }
+}
+
+#[optfield(
+    Opt,
+    attrs,
+    merge_fn,
+    merge_ref_fn,
+    with_fn,
+    merge_arc_fn,
+    try_merge_fn,
+    validate_error = ()
+)]
+#[derive(Clone, Debug, PartialEq)]
+struct Original {
+    #[optfield(merge_if = newer)]
+    version: u32,
+    #[optfield(merge_if = raise_only)]
+    limit: Option<u32>,
+    text: String,
+}
+
+fn original() -> Original {
+    Original {
+        version: 2,
+        limit: Some(10),
+        text: "test".to_string(),
+    }
+}
+
+#[test]
+fn merge_if_rejects() {
+    let opt = Opt {
+        version: Some(1),
+        limit: Some(5),
+        text: Some("merged".to_string()),
+    };
+
+    let expected = Original {
+        text: "merged".to_string(),
+        ..original()
+    };
+
+    let mut merged = original();
+    merged.merge_opt(opt.clone());
+    assert_eq!(merged, expected);
+
+    let mut merged = original();
+    merged.merge_opt_ref(&opt);
+    assert_eq!(merged, expected);
+
+    assert_eq!(original().with_opt(opt.clone()), expected);
+
+    let mut merged = original();
+    merged.try_merge_opt(opt).unwrap();
+    assert_eq!(merged, expected);
+}
+
+#[test]
+fn merge_if_accepts() {
+    let opt = Opt {
+        version: Some(3),
+        limit: Some(20),
+        text: None,
+    };
+
+    let expected = Original {
+        version: 3,
+        limit: Some(20),
+        ..original()
+    };
+
+    let mut merged = original();
+    merged.merge_opt(opt.clone());
+    assert_eq!(merged, expected);
+
+    let mut merged = original();
+    merged.try_merge_opt(opt).unwrap();
+    assert_eq!(merged, expected);
+}
+
+#[test]
+fn merge_if_arc() {
+    let mut shared = Arc::new(original());
+    let snapshot = Arc::clone(&shared);
+
+    // guarded out changes are not changes
+    Original::merge_opt_arc(
+        &mut shared,
+        Opt {
+            version: Some(1),
+            limit: None,
+            text: None,
+        },
+    );
+    assert!(Arc::ptr_eq(&shared, &snapshot));
+
+    Original::merge_opt_arc(
+        &mut shared,
+        Opt {
+            version: Some(5),
+            limit: None,
+            text: None,
+        },
+    );
+    assert_eq!(shared.version, 5);
+    assert_eq!(snapshot.version, 2);
+}
+
+#[test]
+fn merge_if_fill() {
+    fn accept_even(_: &Option<u32>, new: &Option<u32>) -> bool {
+        new.map_or(false, |n| n % 2 == 0)
+    }
+
+    #[optfield(Opt, attrs, fill_fn)]
+    #[derive(Clone, Debug, PartialEq)]
+    struct Original(#[optfield(merge_if = accept_even)] Option<u32>);
+
+    let mut filled = Original(None);
+    filled.fill_opt(Opt(Some(1)));
+    assert_eq!(filled, Original(None));
+
+    filled.fill_opt(Opt(Some(2)));
+    assert_eq!(filled, Original(Some(2)));
+}
@@ -0,0 +1,205 @@
+use optfield::optfield;
+
+#[test]
+fn merge_ref_struct() {
+    #[optfield(Opt, attrs, merge_ref_fn)]
+    #[optfield(OptRewrap, attrs, rewrap, merge_ref_fn = pub merge_rewrap_ref)]
+    #[derive(Clone, Debug, PartialEq)]
+    struct Original<'a, T> {
+        number: u32,
+        text: &'a str,
+        generic: T,
+        optional: Option<String>,
+    }
+
+    let original = Original {
+        number: 12,
+        text: "test",
+        generic: vec![1, 2],
+        optional: Some("optional".to_string()),
+    };
+
+    let opt = Opt {
+        number: None,
+        text: Some("merged"),
+        generic: Some(vec![3]),
+        optional: None,
+    };
+
+    let mut merged = original.clone();
+    merged.merge_opt_ref(&opt);
+
+    assert_eq!(merged.number, original.number);
+    assert_eq!(merged.text, "merged");
+    assert_eq!(merged.generic, vec![3]);
+    assert_eq!(merged.optional, original.optional);
+    // opt is still usable
+    assert_eq!(opt.generic, Some(vec![3]));
+
+    let opt_rewrap = OptRewrap {
+        number: Some(1),
+        text: None,
+        generic: None,
+        optional: Some(None),
+    };
+
+    let mut merged = original.clone();
+    merged.merge_rewrap_ref(&opt_rewrap);
+
+    assert_eq!(merged.number, 1);
+    assert_eq!(merged.optional, None);
+    assert_eq!(merged.generic, original.generic);
+}
+
+#[test]
+fn with_struct() {
+    #[optfield(Opt, attrs, with_fn)]
+    #[derive(Clone, Debug, PartialEq)]
+    struct Original<T> {
+        number: u32,
+        generic: T,
+        optional: Option<u8>,
+    }
+
+    let original = Original {
+        number: 1,
+        generic: "generic".to_string(),
+        optional: None,
+    };
+
+    let with = original
+        .clone()
+        .with_opt(Opt {
+            number: Some(2),
+            generic: None,
+            optional: None,
+        })
+        .with_opt(Opt {
+            number: None,
+            generic: None,
+            optional: Some(3),
+        });
+
+    assert_eq!(
+        with,
+        Original {
+            number: 2,
+            generic: "generic".to_string(),
+            optional: Some(3),
+        }
+    );
+}
+
+#[test]
+fn merge_variants_tuple_struct() {
+    #[optfield(Opt, attrs, merge_ref_fn, with_fn = pub(crate) with)]
+    #[derive(Clone, Debug, PartialEq)]
+    struct Original(i32, String);
+
+    let opt = Opt(None, Some("merged".to_string()));
+
+    let mut merged = Original(1, "test".to_string());
+    merged.merge_opt_ref(&opt);
+    assert_eq!(merged, Original(1, "merged".to_string()));
+
+    let with = Original(1, "test".to_string()).with(Opt(Some(2), None));
+    assert_eq!(with, Original(2, "test".to_string()));
+}
+
+#[test]
+fn merge_variants_cfg_field() {
+    #![allow(unexpected_cfgs)]
+
+    #[optfield(Opt, field_attrs, merge_ref_fn, with_fn)]
+    #[derive(Clone, Debug)]
+    struct Original {
+        #[cfg(some_feature)]
+        feature_field: String,
+        field: i32,
+    }
+
+    let mut original = Original { field: 1 };
+    original.merge_opt_ref(&Opt { field: Some(2) });
+    assert_eq!(original.field, 2);
+
+    let original = original.with_opt(Opt { field: Some(3) });
+    assert_eq!(original.field, 3);
+}
+
+#[test]
+fn merge_arc_struct() {
+    use std::sync::Arc;
+
+    #[optfield(Opt, attrs, merge_arc_fn)]
+    #[derive(Clone, Debug, PartialEq)]
+    struct Original<T> {
+        number: u32,
+        generic: T,
+        optional: Option<u8>,
+    }
+
+    let mut shared = Arc::new(Original {
+        number: 1,
+        generic: "generic".to_string(),
+        optional: Some(2),
+    });
+    let snapshot = Arc::clone(&shared);
+
+    // no-op patch doesn't clone
+    Original::merge_opt_arc(
+        &mut shared,
+        Opt {
+            number: Some(1),
+            generic: None,
+            optional: Some(2),
+        },
+    );
+    assert!(Arc::ptr_eq(&shared, &snapshot));
+
+    Original::merge_opt_arc(
+        &mut shared,
+        Opt {
+            number: Some(3),
+            generic: None,
+            optional: None,
+        },
+    );
+    assert!(!Arc::ptr_eq(&shared, &snapshot));
+    assert_eq!(shared.number, 3);
+    assert_eq!(shared.generic, snapshot.generic);
+    assert_eq!(shared.optional, snapshot.optional);
+    // readers keep their snapshot
+    assert_eq!(snapshot.number, 1);
+
+    // unique Arc is updated in place
+    let before = Arc::as_ptr(&shared);
+    Original::merge_opt_arc(
+        &mut shared,
+        Opt {
+            number: None,
+            generic: Some("changed".to_string()),
+            optional: None,
+        },
+    );
+    assert_eq!(Arc::as_ptr(&shared), before);
+    assert_eq!(shared.generic, "changed");
+}
+
+#[test]
+fn merge_arc_tuple_struct() {
+    use std::sync::Arc;
+
+    #[optfield(Opt, attrs, rewrap, merge_arc_fn = pub merge_arc)]
+    #[derive(Clone, Debug, PartialEq)]
+    struct Original(i32, Option<String>);
+
+    let mut shared = Arc::new(Original(1, Some("test".to_string())));
+    let snapshot = Arc::clone(&shared);
+
+    Original::merge_arc(&mut shared, Opt(None, Some(Some("test".to_string()))));
+    assert!(Arc::ptr_eq(&shared, &snapshot));
+
+    Original::merge_arc(&mut shared, Opt(None, Some(None)));
+    assert_eq!(*shared, Original(1, None));
+    assert_eq!(*snapshot, Original(1, Some("test".to_string())));
+}
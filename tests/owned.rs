@@ -0,0 +1,94 @@
+use optfield::optfield;
+
+#[test]
+fn owned_borrowed_fields() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct Tag(u8);
+
+    #[optfield(Opt, from, owned)]
+    struct Request<'a> {
+        name: &'a str,
+        tags: &'a [Tag],
+        comment: Option<&'a str>,
+        id: u32,
+    }
+
+    fn store(buffer: &str) -> Opt {
+        let tags = vec![Tag(1), Tag(2)];
+
+        Opt::from(Request {
+            name: buffer,
+            tags: &tags,
+            comment: Some(buffer),
+            id: 1,
+        })
+    }
+
+    let buffer = String::from("name");
+    let opt: Opt = store(&buffer);
+    drop(buffer);
+
+    let name: Option<String> = opt.name;
+    let tags: Option<Vec<Tag>> = opt.tags;
+
+    assert_eq!(name.unwrap(), "name");
+    assert_eq!(tags.unwrap(), vec![Tag(1), Tag(2)]);
+    assert_eq!(opt.comment.unwrap(), "name");
+    assert_eq!(opt.id, Some(1));
+}
+
+#[test]
+fn owned_generic_struct() {
+    #[optfield(Opt, from = (owned, ref), owned)]
+    struct Original<'a, 'b, T: 'a + Clone, U: ?Sized>
+    where
+        'b: 'a,
+    {
+        values: &'a [T],
+        value: &'b U,
+        count: usize,
+    }
+
+    let values = vec![1, 2];
+    let original: Original<i32, str> = Original {
+        values: &values,
+        value: "value",
+        count: 2,
+    };
+
+    let opt: Opt<i32, str> = Opt::from(&original);
+    assert_eq!(opt.values.unwrap(), vec![1, 2]);
+    assert_eq!(opt.value.unwrap(), "value");
+
+    let opt = Opt::from(original);
+    assert_eq!(opt.count, Some(2));
+}
+
+#[test]
+fn owned_rewrap_and_tuple_struct() {
+    #[optfield(Opt, from, owned, rewrap, default)]
+    struct Original<'a>(Option<&'a str>, &'a mut String);
+
+    let mut text = "text".to_string();
+    let opt = Opt::from(Original(None, &mut text));
+
+    assert_eq!(opt.0, Some(None));
+    assert_eq!(opt.1.unwrap(), "text");
+
+    let opt: Opt = Opt::default();
+    assert!(opt.0.is_none());
+}
+
+#[test]
+fn owned_debug_and_str_access() {
+    #[optfield(Opt, from, owned, debug, str_access)]
+    struct Original<'a> {
+        name: &'a str,
+    }
+
+    let mut opt = Opt::from(Original { name: "name" });
+    assert_eq!(format!("{:?}", opt), r#"Opt { name: "name" }"#);
+
+    opt.set_str("name", "other").unwrap();
+    assert_eq!(opt.name.unwrap(), "other");
+}
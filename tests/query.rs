@@ -0,0 +1,134 @@
+use optfield::optfield;
+
+#[test]
+fn query_round_trip() {
+    #[optfield(Opt, attrs = (derive(Debug, PartialEq)), from, query)]
+    struct Original {
+        name: String,
+        limit: u32,
+        tag: Option<String>,
+        active: bool,
+    }
+
+    let opt = Opt::from_query("?name=caf%C3%A9+bar&limit=10&unknown=1&&active=true").unwrap();
+
+    assert_eq!(
+        opt,
+        Opt {
+            name: Some("café bar".to_string()),
+            limit: Some(10),
+            tag: None,
+            active: Some(true),
+        }
+    );
+
+    assert_eq!(opt.to_query(), "name=caf%C3%A9+bar&limit=10&active=true");
+    assert_eq!(Opt::from_query(&opt.to_query()).unwrap(), opt);
+
+    let opt = Opt::from(Original {
+        name: "a&b=c".to_string(),
+        limit: 0,
+        tag: Some("x/y".to_string()),
+        active: false,
+    });
+
+    assert_eq!(
+        opt.to_query(),
+        "name=a%26b%3Dc&limit=0&tag=x%2Fy&active=false"
+    );
+}
+
+#[test]
+fn query_errors() {
+    #[optfield(Opt, attrs = (derive(Debug)), from, query)]
+    struct Original {
+        limit: u8,
+    }
+
+    assert_eq!(
+        Opt::from_query("limit=1&limit=2").unwrap_err(),
+        OptQueryError::DuplicateKey("limit".to_string())
+    );
+
+    let error = Opt::from_query("limit=many").unwrap_err();
+    assert_eq!(
+        error,
+        OptQueryError::InvalidValue {
+            key: "limit",
+            message: "invalid digit found in string".to_string(),
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "invalid value for `limit`: invalid digit found in string"
+    );
+
+    assert_eq!(
+        Opt::from_query("limit=%4").unwrap_err(),
+        OptQueryError::InvalidEncoding("%4".to_string())
+    );
+    assert_eq!(
+        Opt::from_query("limit=%FF").unwrap_err(),
+        OptQueryError::InvalidEncoding("%FF".to_string())
+    );
+
+    let opt = Opt::from(Original { limit: 1 });
+    assert_eq!(opt.to_query(), "limit=1");
+}
+
+#[test]
+fn query_rewrap() {
+    #[optfield(Opt, rewrap, from, query)]
+    struct Original {
+        level: Option<u8>,
+    }
+
+    let opt = Opt::from_query("level=3").unwrap();
+    assert_eq!(opt.level, Some(Some(3)));
+
+    let opt = Opt::from(Original { level: None });
+    assert_eq!(opt.to_query(), "");
+}
+
+#[test]
+fn query_tuple_generic_struct() {
+    #[optfield(Opt, from, query)]
+    struct Original<T>(T, String);
+
+    let opt = Opt::<i64>::from_query("0=-1").unwrap();
+
+    assert_eq!(opt.0, Some(-1));
+    assert_eq!(opt.1, None);
+
+    let opt = Opt::from(Original(1, "test".to_string()));
+    assert_eq!(opt.to_query(), "0=1&1=test");
+}
+
+#[test]
+fn query_cfg_field() {
+    #![allow(unexpected_cfgs)]
+
+    #[optfield(Opt, field_attrs, from, query)]
+    struct Original {
+        #[cfg(some_feature)]
+        feature_field: String,
+        field: i32,
+    }
+
+    let opt = Opt::from_query("feature_field=test&field=1").unwrap();
+    assert_eq!(opt.field, Some(1));
+
+    let opt = Opt::from(Original { field: 0 });
+    assert_eq!(opt.to_query(), "field=0");
+}
+
+#[test]
+fn query_unit_struct() {
+    #[optfield(Opt, from, query)]
+    struct Original;
+
+    let opt = Opt::from_query("field=1").unwrap();
+    assert_eq!(opt.to_query(), "");
+
+    let _ = Opt::from(Original);
+}
@@ -0,0 +1,115 @@
+use optfield::optfield;
+
+#[test]
+fn readonly_ignored() {
+    #[optfield(Opt, attrs, merge_fn, merge_ref_fn, with_fn, from)]
+    #[derive(Clone, Debug, PartialEq)]
+    struct Original {
+        #[optfield(readonly)]
+        id: u32,
+        text: String,
+    }
+
+    let original = Original {
+        id: 1,
+        text: "test".to_string(),
+    };
+
+    let opt = Opt::from(original.clone());
+    assert_eq!(opt.id, Some(1));
+
+    let patch = Opt {
+        id: Some(2),
+        text: Some("merged".to_string()),
+    };
+
+    let mut merged = original.clone();
+    merged.merge_opt(patch.clone());
+    assert_eq!(
+        merged,
+        Original {
+            id: 1,
+            text: "merged".to_string()
+        }
+    );
+
+    let mut merged_ref = original.clone();
+    merged_ref.merge_opt_ref(&patch);
+    assert_eq!(merged_ref, merged);
+
+    assert_eq!(original.with_opt(patch), merged);
+}
+
+#[test]
+fn readonly_rejected() {
+    #[optfield(Opt, attrs, merge_fn, fill_fn)]
+    #[derive(Clone, Debug, PartialEq)]
+    struct Original(
+        #[optfield(readonly = reject)] u32,
+        #[optfield(readonly = ignore)] Option<u32>,
+        String,
+    );
+
+    let original = Original(1, None, "test".to_string());
+
+    let mut merged = original.clone();
+    assert_eq!(
+        merged.merge_opt(Opt(Some(2), None, Some("merged".to_string()))),
+        Err("0")
+    );
+    // nothing is merged when a field is rejected
+    assert_eq!(merged, original);
+
+    assert_eq!(
+        merged.merge_opt(Opt(None, Some(3), Some("merged".to_string()))),
+        Ok(())
+    );
+    assert_eq!(merged, Original(1, None, "merged".to_string()));
+
+    // other merge methods ignore rejected fields
+    merged.fill_opt(Opt(Some(2), Some(3), None));
+    assert_eq!(merged, Original(1, None, "merged".to_string()));
+}
+
+#[test]
+fn readonly_rejected_try_merge() {
+    #[derive(Debug, PartialEq)]
+    struct Error(&'static str);
+
+    impl From<&'static str> for Error {
+        fn from(field: &'static str) -> Self {
+            Error(field)
+        }
+    }
+
+    #[optfield(Opt, try_merge_fn, validate_error = Error)]
+    #[derive(Clone, Debug, PartialEq)]
+    struct Original {
+        #[optfield(readonly = reject)]
+        r#type: u32,
+        text: String,
+    }
+
+    let original = Original {
+        r#type: 1,
+        text: "test".to_string(),
+    };
+
+    let mut merged = original.clone();
+    assert_eq!(
+        merged.try_merge_opt(Opt {
+            r#type: Some(2),
+            text: Some("merged".to_string()),
+        }),
+        Err(Error("type"))
+    );
+    assert_eq!(merged, original);
+
+    merged
+        .try_merge_opt(Opt {
+            r#type: None,
+            text: Some("merged".to_string()),
+        })
+        .unwrap();
+    assert_eq!(merged.text, "merged");
+}
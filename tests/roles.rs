@@ -0,0 +1,149 @@
+use optfield::optfield;
+
+#[allow(dead_code)]
+enum Role {
+    Admin,
+    Owner,
+    Guest,
+}
+
+#[test]
+fn roles_restrict() {
+    #[optfield(Opt, attrs = (derive(Clone, Debug, PartialEq)), from, roles = Role)]
+    struct Original {
+        name: String,
+        #[optfield(roles = (Admin, Owner))]
+        email: String,
+        #[optfield(roles = (Admin))]
+        verified: bool,
+        #[optfield(roles = ())]
+        id: u64,
+    }
+
+    let opt = Opt::from(Original {
+        name: "test".to_string(),
+        email: "test@example.com".to_string(),
+        verified: true,
+        id: 1,
+    });
+
+    let mut guest = opt.clone();
+    assert_eq!(
+        guest.restrict(&Role::Guest),
+        vec!["email", "verified", "id"]
+    );
+    assert_eq!(
+        guest,
+        Opt {
+            name: Some("test".to_string()),
+            email: None,
+            verified: None,
+            id: None,
+        }
+    );
+
+    let mut owner = opt.clone();
+    assert_eq!(owner.restrict(&Role::Owner), vec!["verified", "id"]);
+    assert_eq!(owner.email, Some("test@example.com".to_string()));
+
+    let mut admin = opt;
+    assert_eq!(admin.restrict(&Role::Admin), vec!["id"]);
+    assert_eq!(admin.verified, Some(true));
+
+    assert!(admin.restrict(&Role::Admin).is_empty());
+}
+
+#[test]
+fn roles_check() {
+    #[optfield(Opt, from, roles = Role)]
+    struct Original {
+        name: String,
+        #[optfield(roles = (Admin, Owner))]
+        email: String,
+    }
+
+    let opt = Opt {
+        name: Some("test".to_string()),
+        email: Some("test@example.com".to_string()),
+    };
+
+    assert_eq!(opt.check(&Role::Owner), Ok(()));
+
+    let error = opt.check(&Role::Guest).unwrap_err();
+    assert_eq!(error.fields, vec!["email"]);
+    assert_eq!(error.to_string(), "forbidden fields: email");
+
+    let opt = Opt::from(Original {
+        name: "test".to_string(),
+        email: String::new(),
+    });
+    assert!(opt.check(&Role::Admin).is_ok());
+
+    let opt = Opt {
+        name: Some("test".to_string()),
+        email: None,
+    };
+    assert!(opt.check(&Role::Guest).is_ok());
+}
+
+#[test]
+fn roles_path_tuple_struct() {
+    mod auth {
+        #[allow(dead_code)]
+        pub enum Role {
+            Admin,
+            Guest,
+        }
+    }
+
+    #[optfield(Opt, from, roles = auth::Role)]
+    struct Original(String, #[optfield(roles = (Admin))] u64);
+
+    let mut opt = Opt::from(Original("test".to_string(), 1));
+
+    assert_eq!(opt.restrict(&auth::Role::Guest), vec!["1"]);
+    assert_eq!(opt.1, None);
+}
+
+#[test]
+fn roles_cfg_field() {
+    #![allow(unexpected_cfgs)]
+
+    #[optfield(Opt, field_attrs, from, roles = Role)]
+    struct Original {
+        #[cfg(some_feature)]
+        #[optfield(roles = (Admin))]
+        feature_field: String,
+        #[optfield(roles = (Admin))]
+        field: i32,
+    }
+
+    let mut opt = Opt::from(Original { field: 1 });
+
+    assert_eq!(opt.restrict(&Role::Guest), vec!["field"]);
+}
+
+#[test]
+fn roles_multiple_attributes() {
+    #[optfield(Opt, from, roles = Role)]
+    #[optfield(Patch, merge_fn)]
+    struct Original {
+        name: String,
+        #[optfield(roles = (Admin))]
+        email: String,
+    }
+
+    let mut original = Original {
+        name: "name".to_string(),
+        email: "email".to_string(),
+    };
+
+    original.merge_opt(Patch {
+        name: None,
+        email: Some("other".to_string()),
+    });
+
+    let mut opt = Opt::from(original);
+
+    assert_eq!(opt.restrict(&Role::Guest), vec!["email"]);
+}
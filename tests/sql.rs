@@ -0,0 +1,152 @@
+use optfield::optfield;
+
+trait ToSqlValue {
+    fn to_sql(&self) -> String;
+}
+
+impl ToSqlValue for String {
+    fn to_sql(&self) -> String {
+        format!("'{}'", self)
+    }
+}
+
+impl ToSqlValue for i32 {
+    fn to_sql(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl<T: ToSqlValue> ToSqlValue for Option<T> {
+    fn to_sql(&self) -> String {
+        match self {
+            Some(value) => value.to_sql(),
+            None => "NULL".to_string(),
+        }
+    }
+}
+
+fn values(values: Vec<&dyn ToSqlValue>) -> Vec<String> {
+    values.iter().map(|value| value.to_sql()).collect()
+}
+
+#[test]
+fn sql_question_placeholders() {
+    #[optfield(Opt, from, sql)]
+    struct Original {
+        name: String,
+        #[optfield(column = "user_age")]
+        age: i32,
+        email: Option<String>,
+    }
+
+    let opt = Opt {
+        name: Some("test".to_string()),
+        age: Some(30),
+        email: None,
+    };
+
+    let (sql, update_values) = opt.to_update("users").unwrap();
+
+    assert_eq!(sql, "UPDATE users SET name = ?, user_age = ?");
+    assert_eq!(values(update_values), vec!["'test'", "30"]);
+
+    let opt = Opt::from(Original {
+        name: "test".to_string(),
+        age: 30,
+        email: Some("test@example.com".to_string()),
+    });
+
+    let (sql, _) = opt.to_update("users").unwrap();
+    assert_eq!(sql, "UPDATE users SET name = ?, user_age = ?, email = ?");
+}
+
+#[test]
+fn sql_numbered_placeholders() {
+    #[optfield(Opt, from, sql = numbered)]
+    struct Original {
+        name: String,
+        age: i32,
+        email: String,
+    }
+
+    let opt = Opt {
+        name: Some("test".to_string()),
+        age: None,
+        email: Some("test@example.com".to_string()),
+    };
+
+    let (sql, update_values) = opt.to_update("users").unwrap();
+
+    assert_eq!(sql, "UPDATE users SET name = $1, email = $2");
+    assert_eq!(values(update_values), vec!["'test'", "'test@example.com'"]);
+
+    let opt = Opt::from(Original {
+        name: String::new(),
+        age: 1,
+        email: String::new(),
+    });
+    assert!(opt.to_update("users").is_some());
+}
+
+#[test]
+fn sql_nothing_set() {
+    #[optfield(Opt, from, sql)]
+    struct Original {
+        name: String,
+    }
+
+    let opt = Opt { name: None };
+    assert!(opt.to_update("users").is_none());
+
+    let opt = Opt::from(Original {
+        name: String::new(),
+    });
+    assert!(opt.to_update("users").is_some());
+}
+
+#[test]
+fn sql_rewrap_null() {
+    #[optfield(Opt, rewrap, from, sql)]
+    struct Original {
+        email: Option<String>,
+    }
+
+    let opt = Opt::from(Original { email: None });
+
+    let (sql, update_values) = opt.to_update("users").unwrap();
+
+    assert_eq!(sql, "UPDATE users SET email = ?");
+    assert_eq!(values(update_values), vec!["NULL"]);
+}
+
+#[test]
+fn sql_generic_struct() {
+    #[optfield(Opt, from, sql)]
+    struct Original<T> {
+        value: T,
+    }
+
+    let opt = Opt::from(Original { value: 1 });
+
+    let (sql, update_values) = opt.to_update("items").unwrap();
+
+    assert_eq!(sql, "UPDATE items SET value = ?");
+    assert_eq!(values(update_values), vec!["1"]);
+}
+
+#[test]
+fn sql_cfg_field() {
+    #![allow(unexpected_cfgs)]
+
+    #[optfield(Opt, field_attrs, from, sql)]
+    struct Original {
+        #[cfg(some_feature)]
+        feature_field: String,
+        field: i32,
+    }
+
+    let opt = Opt::from(Original { field: 1 });
+
+    let (sql, _) = opt.to_update("items").unwrap();
+    assert_eq!(sql, "UPDATE items SET field = ?");
+}
@@ -0,0 +1,135 @@
+use optfield::optfield;
+
+#[test]
+fn str_access_set_and_get() {
+    #[optfield(Opt, attrs = (derive(Debug, Default, PartialEq)), from, str_access)]
+    #[derive(Default)]
+    struct Original {
+        name: String,
+        #[optfield(alias = "max_connections", alias = "max-conns")]
+        max_conns: u32,
+        ratio: Option<f32>,
+    }
+
+    let mut opt = Opt::from(Original::default());
+
+    opt.set_str("name", "server").unwrap();
+    opt.set_str("max-conns", "10").unwrap();
+    opt.set_str("ratio", "0.5").unwrap();
+
+    assert_eq!(
+        opt,
+        Opt {
+            name: Some("server".to_string()),
+            max_conns: Some(10),
+            ratio: Some(0.5),
+        }
+    );
+
+    assert_eq!(opt.get_debug("name"), Some("\"server\"".to_string()));
+    assert_eq!(opt.get_debug("max_connections"), Some("10".to_string()));
+    assert_eq!(opt.get_debug("unknown"), None);
+
+    opt.max_conns = None;
+    assert_eq!(opt.get_debug("max_conns"), None);
+}
+
+#[test]
+fn str_access_errors() {
+    #[optfield(Opt, from, str_access)]
+    struct Original {
+        #[optfield(alias = "count")]
+        number: u8,
+    }
+
+    let mut opt = Opt::from(Original { number: 0 });
+    opt.number = None;
+
+    let error = opt.set_str("unknown", "1").unwrap_err();
+    assert_eq!(error, OptSetError::UnknownKey("unknown".to_string()));
+    assert_eq!(error.to_string(), "unknown key `unknown`");
+
+    let error = opt.set_str("count", "300").unwrap_err();
+    assert_eq!(
+        error,
+        OptSetError::InvalidValue {
+            key: "number",
+            message: "number too large to fit in target type".to_string(),
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "invalid value for `number`: number too large to fit in target type"
+    );
+    assert_eq!(opt.number, None);
+}
+
+#[test]
+fn str_access_rewrap() {
+    #[optfield(Opt, rewrap, from, str_access)]
+    struct Original {
+        optional: Option<i32>,
+    }
+
+    let mut opt = Opt::from(Original { optional: None });
+    assert_eq!(opt.get_debug("optional"), Some("None".to_string()));
+
+    opt.set_str("optional", "-1").unwrap();
+
+    assert_eq!(opt.optional, Some(Some(-1)));
+    assert_eq!(opt.get_debug("optional"), Some("Some(-1)".to_string()));
+}
+
+#[test]
+fn str_access_tuple_generic_struct() {
+    #[optfield(Opt, from, str_access)]
+    struct Original<T>(T, bool);
+
+    let mut opt = Opt::from(Original(0u64, false));
+
+    opt.set_str("0", "42").unwrap();
+    opt.set_str("1", "true").unwrap();
+
+    assert_eq!(opt.0, Some(42));
+    assert_eq!(opt.get_debug("1"), Some("true".to_string()));
+}
+
+#[test]
+fn str_access_cfg_field() {
+    #![allow(unexpected_cfgs)]
+
+    #[optfield(Opt, field_attrs, from, str_access)]
+    struct Original {
+        #[cfg(some_feature)]
+        feature_field: String,
+        field: i32,
+    }
+
+    let mut opt = Opt::from(Original { field: 0 });
+
+    assert_eq!(
+        opt.set_str("feature_field", "test"),
+        Err(OptSetError::UnknownKey("feature_field".to_string()))
+    );
+
+    opt.set_str("field", "1").unwrap();
+    assert_eq!(opt.field, Some(1));
+}
+
+#[test]
+fn str_access_sensitive_field() {
+    #[optfield(Opt, from, str_access)]
+    struct Original {
+        name: String,
+        #[optfield(sensitive)]
+        password: String,
+    }
+
+    let opt = Opt::from(Original {
+        name: "test".to_string(),
+        password: "secret".to_string(),
+    });
+
+    assert_eq!(opt.get_debug("name"), Some(r#""test""#.to_string()));
+    assert_eq!(opt.get_debug("password"), Some("***".to_string()));
+}
@@ -0,0 +1,127 @@
+use optfield::optfield;
+
+#[test]
+fn three_way_struct() {
+    #[optfield(Opt, attrs, three_way)]
+    #[derive(Clone, Debug, PartialEq)]
+    struct Original<'a, T> {
+        number: u32,
+        text: &'a str,
+        generic: T,
+        optional: Option<&'a [u8]>,
+    }
+
+    let base = Original {
+        number: 1,
+        text: "base",
+        generic: "generic".to_string(),
+        optional: None,
+    };
+
+    let ours = Opt {
+        number: Some(2),
+        text: Some("same"),
+        generic: Some("generic".to_string()),
+        optional: None,
+    };
+
+    let theirs = Opt {
+        number: None,
+        text: Some("same"),
+        generic: Some("changed".to_string()),
+        optional: Some(&[1, 2]),
+    };
+
+    let merged = Opt::three_way(&base, ours.clone(), theirs.clone()).unwrap();
+
+    assert_eq!(merged.number, Some(2));
+    assert_eq!(merged.text, Some("same"));
+    assert_eq!(merged.generic, Some("changed".to_string()));
+    assert_eq!(merged.optional, Some(&[1, 2][..]));
+
+    let theirs = Opt {
+        number: Some(3),
+        optional: Some(&[3]),
+        ..theirs
+    };
+    let ours = Opt {
+        optional: Some(&[4]),
+        ..ours
+    };
+
+    let conflicts = match Opt::three_way(&base, ours, theirs) {
+        Ok(_) => panic!("expected conflicts"),
+        Err(conflicts) => conflicts,
+    };
+
+    assert_eq!(conflicts.fields(), vec!["number", "optional"]);
+    assert_eq!(conflicts.number, Some((2, 3)));
+    assert_eq!(conflicts.text, None);
+    assert_eq!(conflicts.generic, None);
+    assert_eq!(conflicts.optional, Some((&[4][..], &[3][..])));
+}
+
+#[test]
+fn three_way_tuple_struct() {
+    #[optfield(Opt, attrs, rewrap, three_way = pub merge3)]
+    #[derive(Clone, Debug, PartialEq)]
+    struct Original(i32, Option<String>);
+
+    let base = Original(1, Some("base".to_string()));
+
+    let ours = Opt(Some(2), Some(None));
+    let theirs = Opt(Some(1), None);
+
+    let merged = Opt::merge3(&base, ours, theirs).unwrap();
+
+    assert_eq!(merged, Opt(Some(2), Some(None)));
+
+    let ours = Opt(None, Some(None));
+    let theirs = Opt(None, Some(Some("theirs".to_string())));
+
+    let conflicts = Opt::merge3(&base, ours, theirs).unwrap_err();
+
+    assert_eq!(conflicts.fields(), vec!["1"]);
+    assert_eq!(conflicts.0, None);
+    assert_eq!(conflicts.1, Some((None, Some("theirs".to_string()))));
+}
+
+#[test]
+fn three_way_cfg_field() {
+    #![allow(unexpected_cfgs)]
+
+    #[optfield(Opt, field_attrs, three_way)]
+    struct Original {
+        #[cfg(some_feature)]
+        feature_field: String,
+        field: i32,
+    }
+
+    let base = Original { field: 1 };
+
+    let merged = Opt::three_way(&base, Opt { field: Some(2) }, Opt { field: None }).unwrap();
+    assert_eq!(merged.field, Some(2));
+
+    let conflicts = match Opt::three_way(&base, Opt { field: Some(2) }, Opt { field: Some(3) }) {
+        Ok(_) => panic!("expected conflicts"),
+        Err(conflicts) => conflicts,
+    };
+    assert_eq!(conflicts.field, Some((2, 3)));
+}
+
+#[test]
+fn three_way_raw_identifier() {
+    #[optfield(Opt, three_way)]
+    struct Original {
+        r#type: i32,
+    }
+
+    let base = Original { r#type: 1 };
+
+    let conflicts = match Opt::three_way(&base, Opt { r#type: Some(2) }, Opt { r#type: Some(3) }) {
+        Ok(_) => panic!("expected conflicts"),
+        Err(conflicts) => conflicts,
+    };
+    assert_eq!(conflicts.fields(), vec!["type"]);
+    assert_eq!(conflicts.r#type, Some((2, 3)));
+}
@@ -0,0 +1,176 @@
+use optfield::optfield;
+
+#[derive(Debug, PartialEq)]
+enum Error {
+    Empty,
+    TooLarge,
+    Invalid(&'static str),
+}
+
+fn not_empty(text: &&str) -> Result<(), Error> {
+    if text.is_empty() {
+        Err(Error::Empty)
+    } else {
+        Ok(())
+    }
+}
+
+fn not_too_large(number: &u32) -> Result<(), &'static str> {
+    if *number > 100 {
+        Err("too large")
+    } else {
+        Ok(())
+    }
+}
+
+impl From<&'static str> for Error {
+    fn from(_: &'static str) -> Self {
+        Error::TooLarge
+    }
+}
+
+mod validators {
+    use super::Error;
+
+    pub fn min_below_max(original: &super::Range) -> Result<(), Error> {
+        match original.max {
+            Some(max) if original.min > max => Err(Error::Invalid("min is above max")),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[optfield(Opt, attrs, try_merge_fn, validate = validators::min_below_max, validate_error = Error)]
+#[derive(Clone, Debug, PartialEq)]
+struct Range {
+    #[optfield(validate = not_too_large)]
+    min: u32,
+    max: Option<u32>,
+}
+
+#[test]
+fn try_merge_struct() {
+    let original = Range {
+        min: 1,
+        max: Some(10),
+    };
+
+    let mut merged = original.clone();
+    merged
+        .try_merge_opt(Opt {
+            min: Some(5),
+            max: Some(20),
+        })
+        .unwrap();
+
+    assert_eq!(
+        merged,
+        Range {
+            min: 5,
+            max: Some(20)
+        }
+    );
+
+    // field validator fails before anything is assigned
+    let mut merged = original.clone();
+    let result = merged.try_merge_opt(Opt {
+        min: Some(200),
+        max: Some(300),
+    });
+
+    assert_eq!(result, Err(Error::TooLarge));
+    assert_eq!(merged, original);
+
+    // struct validator fails after the patch is applied and rolls it back
+    let mut merged = original.clone();
+    let result = merged.try_merge_opt(Opt {
+        min: Some(50),
+        max: Some(20),
+    });
+
+    assert_eq!(result, Err(Error::Invalid("min is above max")));
+    assert_eq!(merged, original);
+}
+
+#[test]
+fn try_merge_field_validators_only() {
+    #[optfield(Opt, attrs, rewrap, try_merge_fn = pub try_merge, validate_error = Error)]
+    #[derive(Clone, Debug, PartialEq)]
+    struct Original<'a, T>(#[optfield(validate = not_empty)] &'a str, T, Option<String>);
+
+    let original = Original("test", 1, None);
+
+    let mut merged = original.clone();
+    merged
+        .try_merge(Opt(Some("merged"), None, Some(Some("some".to_string()))))
+        .unwrap();
+    assert_eq!(merged, Original("merged", 1, Some("some".to_string())));
+
+    let mut merged = original.clone();
+    assert_eq!(
+        merged.try_merge(Opt(Some(""), Some(2), None)),
+        Err(Error::Empty)
+    );
+    assert_eq!(merged, original);
+}
+
+#[test]
+fn try_merge_cfg_field() {
+    #![allow(unexpected_cfgs)]
+
+    fn positive(original: &Original) -> Result<(), Error> {
+        if original.field > 0 {
+            Ok(())
+        } else {
+            Err(Error::Invalid("not positive"))
+        }
+    }
+
+    #[optfield(Opt, field_attrs, try_merge_fn, validate = positive, validate_error = Error)]
+    #[derive(Clone, Debug)]
+    struct Original {
+        #[cfg(some_feature)]
+        #[optfield(validate = not_empty)]
+        feature_field: &'static str,
+        field: i32,
+    }
+
+    let mut original = Original { field: 1 };
+
+    original.try_merge_opt(Opt { field: Some(2) }).unwrap();
+    assert_eq!(original.field, 2);
+
+    assert!(original.try_merge_opt(Opt { field: Some(-1) }).is_err());
+    assert_eq!(original.field, 2);
+}
+
+#[test]
+fn try_merge_raw_identifier() {
+    fn valid(original: &Original) -> Result<(), Error> {
+        if original.r#type.is_empty() {
+            Err(Error::Empty)
+        } else {
+            Ok(())
+        }
+    }
+
+    #[optfield(Opt, try_merge_fn, validate = valid, validate_error = Error)]
+    struct Original {
+        r#type: &'static str,
+    }
+
+    let mut original = Original { r#type: "test" };
+
+    original
+        .try_merge_opt(Opt {
+            r#type: Some("merged"),
+        })
+        .unwrap();
+    assert_eq!(original.r#type, "merged");
+
+    assert_eq!(
+        original.try_merge_opt(Opt { r#type: Some("") }),
+        Err(Error::Empty)
+    );
+    assert_eq!(original.r#type, "merged");
+}
@@ -0,0 +1,159 @@
+use std::marker::PhantomData;
+
+use optfield::optfield;
+
+#[test]
+fn unused_generics_prune() {
+    #[optfield(Opt, from, owned, unused_generics = prune, default)]
+    struct Request<'a, T> {
+        name: &'a str,
+        value: T,
+    }
+
+    fn store<T>(name: &str, value: T) -> Opt<T> {
+        Opt::from(Request { name, value })
+    }
+
+    let buffer = String::from("name");
+    let opt: Opt<u8> = store(&buffer, 1);
+    drop(buffer);
+
+    assert_eq!(opt.name.unwrap(), "name");
+    assert_eq!(opt.value, Some(1));
+
+    let opt: Opt<u8> = Opt::default();
+    assert!(opt.name.is_none());
+}
+
+#[test]
+fn unused_generics_phantom() {
+    #[optfield(Opt, from, owned, unused_generics = phantom, default, debug)]
+    struct Request<'a, 'b> {
+        name: &'a str,
+        tags: &'b [u8],
+    }
+
+    let opt: Opt<'static, 'static> = Opt::from(Request {
+        name: "name",
+        tags: &[1, 2],
+    });
+
+    let _phantom: PhantomData<(&(), &())> = opt._phantom;
+    assert_eq!(
+        format!("{:?}", opt),
+        r#"Opt { name: "name", tags: [1, 2] }"#
+    );
+
+    let opt: Opt = Opt::default();
+    assert!(opt.tags.is_none());
+}
+
+#[test]
+fn unused_generics_phantom_tuple_struct() {
+    #[optfield(Opt, from = (owned, ref), owned, unused_generics = phantom)]
+    struct Request<'a>(&'a str, u8);
+
+    let request = Request("name", 1);
+
+    let opt = Opt::from(&request);
+    assert_eq!(opt.0.unwrap(), "name");
+
+    let opt = Opt::from(request);
+    assert_eq!(opt.1, Some(1));
+    let _phantom: PhantomData<(&(),)> = opt.2;
+}
+
+#[test]
+fn unused_generics_phantom_merge() {
+    #[optfield(Opt, from, merge_fn, unused_generics = phantom)]
+    struct Original<'a, T> {
+        text: &'a str,
+        value: T,
+    }
+
+    let mut original = Original {
+        text: "text",
+        value: 1,
+    };
+
+    // nothing is unused, so no marker field is added
+    original.merge_opt(Opt {
+        text: Some("other"),
+        value: None,
+    });
+
+    assert_eq!(original.text, "other");
+
+    let opt = Opt::from(original);
+    assert_eq!(opt.value, Some(1));
+}
+
+#[test]
+fn unused_generics_phantom_nested() {
+    mod lookup {
+        pub struct Lookup(pub Vec<String>);
+
+        pub enum LookupMaskError {
+            UnknownField(String),
+            NestedPath(String),
+        }
+
+        impl From<u8> for Lookup {
+            fn from(_: u8) -> Self {
+                Lookup(vec!["key".to_string()])
+            }
+        }
+
+        impl Lookup {
+            pub fn field_mask(&self) -> Vec<String> {
+                self.0.clone()
+            }
+
+            pub fn from_field_mask<T>(mask: &[&str], _: &T) -> Result<Self, LookupMaskError> {
+                match mask.iter().find(|path| **path != "key") {
+                    Some(path) if path.contains('.') => {
+                        Err(LookupMaskError::NestedPath(path.to_string()))
+                    }
+                    Some(path) => Err(LookupMaskError::UnknownField(path.to_string())),
+                    None => Ok(Lookup(vec!["key".to_string()])),
+                }
+            }
+        }
+    }
+
+    // the opt struct only holds the nested type, which doesn't use T
+    #[optfield(Opt, field_mask, unused_generics = phantom)]
+    struct Original<T> {
+        #[optfield(nested = lookup::Lookup)]
+        value: T,
+    }
+
+    let original = Original { value: 1u8 };
+
+    let opt = Opt::from_field_mask(&["value.key"], &original).unwrap();
+    assert_eq!(opt.field_mask(), vec!["value.key"]);
+
+    let _phantom: PhantomData<(fn() -> u8,)> = opt._phantom;
+}
+
+#[test]
+fn unused_generics_phantom_visibility() {
+    mod request {
+        use optfield::optfield;
+
+        #[optfield(pub Opt, from, owned, unused_generics = phantom)]
+        pub struct Request<'a> {
+            pub name: &'a str,
+        }
+    }
+
+    let opt = request::Opt {
+        name: Some("name".to_string()),
+        _phantom: PhantomData,
+    };
+
+    assert_eq!(opt.name.unwrap(), "name");
+
+    let opt = request::Opt::from(request::Request { name: "other" });
+    assert_eq!(opt.name.unwrap(), "other");
+}
@@ -0,0 +1,220 @@
+use optfield::optfield;
+
+#[test]
+fn update_enum_round_trip() {
+    #[optfield(Opt, attrs, update_enum = (derive(Clone, Debug, PartialEq)))]
+    #[derive(Debug, PartialEq)]
+    struct Original {
+        text: String,
+        number: i32,
+        optional: Option<u8>,
+    }
+
+    let opt = Opt {
+        text: Some("new".to_string()),
+        number: None,
+        optional: Some(1),
+    };
+
+    let updates = opt.into_updates();
+
+    assert_eq!(
+        updates,
+        vec![
+            OriginalUpdate::Text("new".to_string()),
+            OriginalUpdate::Optional(1),
+        ]
+    );
+
+    let opt: Opt = updates.clone().into_iter().collect();
+
+    assert_eq!(
+        opt,
+        Opt {
+            text: Some("new".to_string()),
+            number: None,
+            optional: Some(1),
+        }
+    );
+
+    let mut original = Original {
+        text: "old".to_string(),
+        number: 1,
+        optional: None,
+    };
+
+    for update in updates {
+        original.apply_update(update);
+    }
+
+    assert_eq!(
+        original,
+        Original {
+            text: "new".to_string(),
+            number: 1,
+            optional: Some(1),
+        }
+    );
+}
+
+#[test]
+fn update_enum_last_update_wins() {
+    #[optfield(Opt, update_enum)]
+    struct Original {
+        number: i32,
+    }
+
+    let opt: Opt = vec![OriginalUpdate::Number(1), OriginalUpdate::Number(2)]
+        .into_iter()
+        .collect();
+
+    assert_eq!(opt.number, Some(2));
+
+    let mut original = Original { number: 0 };
+    original.apply_update(OriginalUpdate::Number(3));
+
+    assert_eq!(original.number, 3);
+}
+
+#[test]
+fn update_enum_rewrap() {
+    #[optfield(Opt, rewrap, update_enum)]
+    struct Original {
+        optional: Option<u8>,
+    }
+
+    let opt = Opt {
+        optional: Some(None),
+    };
+
+    let updates = opt.into_updates();
+    assert!(matches!(updates[..], [OriginalUpdate::Optional(None)]));
+
+    let mut original = Original { optional: Some(1) };
+
+    for update in updates {
+        original.apply_update(update);
+    }
+
+    assert_eq!(original.optional, None);
+}
+
+#[test]
+fn update_enum_tuple_generic_struct() {
+    #[optfield(Opt, update_enum = (derive(Debug, PartialEq)))]
+    struct Original<'a, T>(&'a str, T);
+
+    let updates = Opt(None, Some(1u8)).into_updates();
+
+    assert_eq!(updates, vec![OriginalUpdate::_1(1)]);
+
+    let mut original = Original("test", 0);
+    original.apply_update(OriginalUpdate::_0("new"));
+
+    assert_eq!(original.0, "new");
+    assert_eq!(original.1, 0);
+}
+
+#[test]
+fn update_enum_cfg_field() {
+    #![allow(unexpected_cfgs)]
+
+    #[optfield(Opt, field_attrs, update_enum)]
+    struct Original {
+        #[cfg(some_feature)]
+        feature_field: String,
+        field: i32,
+    }
+
+    let opt: Opt = vec![OriginalUpdate::Field(1)].into_iter().collect();
+
+    let mut original = Original { field: 0 };
+
+    for update in opt.into_updates() {
+        original.apply_update(update);
+    }
+
+    assert_eq!(original.field, 1);
+}
+
+#[test]
+fn update_enum_unit_struct() {
+    #[optfield(Opt, update_enum)]
+    struct Original;
+
+    let opt: Opt = Vec::<OriginalUpdate>::new().into_iter().collect();
+
+    assert!(opt.into_updates().is_empty());
+
+    let _ = Original;
+}
+
+#[test]
+fn update_enum_readonly_and_merge_if() {
+    fn newer(current: &u32, new: &u32) -> bool {
+        new > current
+    }
+
+    fn is_set(_: &Option<String>, new: &Option<String>) -> bool {
+        new.as_ref().map_or(false, |new| !new.is_empty())
+    }
+
+    #[optfield(Opt, update_enum)]
+    struct Original {
+        #[optfield(readonly)]
+        id: u32,
+        #[optfield(merge_if = newer)]
+        version: u32,
+        #[optfield(merge_if = is_set)]
+        name: Option<String>,
+    }
+
+    let mut original = Original {
+        id: 1,
+        version: 2,
+        name: None,
+    };
+
+    original.apply_update(OriginalUpdate::Id(2));
+    assert_eq!(original.id, 1);
+
+    original.apply_update(OriginalUpdate::Version(1));
+    assert_eq!(original.version, 2);
+
+    original.apply_update(OriginalUpdate::Version(3));
+    assert_eq!(original.version, 3);
+
+    original.apply_update(OriginalUpdate::Name(String::new()));
+    assert_eq!(original.name, None);
+
+    original.apply_update(OriginalUpdate::Name("name".to_string()));
+    assert_eq!(original.name, Some("name".to_string()));
+
+    let opt: Opt = vec![OriginalUpdate::Id(2)].into_iter().collect();
+    assert_eq!(opt.id, Some(2));
+}
+
+#[test]
+fn update_enum_underscore_names() {
+    #[optfield(Opt, update_enum = (derive(Debug, PartialEq)))]
+    struct Original {
+        _1: i32,
+        __: i32,
+    }
+
+    let opt = Opt {
+        _1: Some(1),
+        __: Some(2),
+    };
+
+    assert_eq!(
+        opt.into_updates(),
+        vec![OriginalUpdate::Field1(1), OriginalUpdate::Field(2)]
+    );
+
+    let mut original = Original { _1: 0, __: 0 };
+    original.apply_update(OriginalUpdate::Field(3));
+
+    assert_eq!(original._1, 0);
+    assert_eq!(original.__, 3);
+}
@@ -0,0 +1,178 @@
+use optfield::optfield;
+
+#[test]
+fn versions_for_version() {
+    #[optfield(Opt, attrs = (derive(Clone, Debug, PartialEq)), from, versions)]
+    struct Original {
+        name: String,
+        #[optfield(since = 3)]
+        theme: String,
+        #[optfield(until = 4)]
+        legacy: bool,
+        #[optfield(since = 2, until = 5)]
+        layout: u8,
+    }
+
+    let opt = Opt::from(Original {
+        name: "test".to_string(),
+        theme: "dark".to_string(),
+        legacy: true,
+        layout: 1,
+    });
+
+    assert_eq!(
+        opt.clone().for_version(1),
+        Opt {
+            name: Some("test".to_string()),
+            theme: None,
+            legacy: Some(true),
+            layout: None,
+        }
+    );
+    assert_eq!(opt.clone().for_version(3), opt);
+    assert_eq!(
+        opt.clone().for_version(5),
+        Opt {
+            name: Some("test".to_string()),
+            theme: Some("dark".to_string()),
+            legacy: None,
+            layout: Some(1),
+        }
+    );
+    assert_eq!(
+        opt.for_version(6),
+        Opt {
+            name: Some("test".to_string()),
+            theme: Some("dark".to_string()),
+            legacy: None,
+            layout: None,
+        }
+    );
+}
+
+#[test]
+fn versions_min_version() {
+    #[optfield(Opt, from, versions)]
+    struct Original {
+        name: String,
+        #[optfield(since = 3)]
+        theme: String,
+        #[optfield(since = 2)]
+        layout: u8,
+    }
+
+    let opt = Opt {
+        name: Some("test".to_string()),
+        theme: None,
+        layout: None,
+    };
+    assert_eq!(opt.min_version(), Some(0));
+
+    let opt = Opt {
+        name: None,
+        theme: None,
+        layout: Some(1),
+    };
+    assert_eq!(opt.min_version(), Some(2));
+
+    let opt = Opt::from(Original {
+        name: "test".to_string(),
+        theme: "dark".to_string(),
+        layout: 1,
+    });
+    assert_eq!(opt.min_version(), Some(3));
+}
+
+#[test]
+fn versions_tuple_generic_struct() {
+    #[optfield(Opt, from, versions)]
+    struct Original<T>(T, #[optfield(since = 2)] String);
+
+    let opt = Opt::from(Original(1, "test".to_string()));
+
+    assert_eq!(opt.min_version(), Some(2));
+
+    let opt = opt.for_version(1);
+
+    assert_eq!(opt.0, Some(1));
+    assert_eq!(opt.1, None);
+}
+
+#[test]
+fn versions_cfg_field() {
+    #![allow(unexpected_cfgs)]
+
+    #[optfield(Opt, field_attrs, from, versions)]
+    struct Original {
+        #[cfg(some_feature)]
+        #[optfield(since = 4)]
+        feature_field: String,
+        #[optfield(since = 2)]
+        field: i32,
+    }
+
+    let opt = Opt::from(Original { field: 1 });
+
+    assert_eq!(opt.min_version(), Some(2));
+    assert_eq!(opt.for_version(1).field, None);
+}
+
+#[test]
+fn versions_multiple_attributes() {
+    #[optfield(Opt, from, versions)]
+    #[optfield(Patch, merge_fn)]
+    struct Original {
+        name: String,
+        #[optfield(since = 2)]
+        theme: String,
+    }
+
+    let mut original = Original {
+        name: "test".to_string(),
+        theme: "dark".to_string(),
+    };
+
+    original.merge_opt(Patch {
+        name: None,
+        theme: Some("light".to_string()),
+    });
+
+    let opt = Opt::from(original);
+
+    assert_eq!(opt.min_version(), Some(2));
+    assert_eq!(opt.for_version(1).theme, None);
+}
+
+#[test]
+fn versions_conflicting_bounds() {
+    #[optfield(Opt, from, versions)]
+    struct Original {
+        #[optfield(since = 6)]
+        theme: String,
+        #[optfield(until = 5)]
+        legacy: bool,
+        #[optfield(until = 7)]
+        layout: u8,
+    }
+
+    let opt = Opt::from(Original {
+        theme: "dark".to_string(),
+        legacy: true,
+        layout: 1,
+    });
+    assert_eq!(opt.min_version(), None);
+
+    let opt = Opt {
+        theme: Some("dark".to_string()),
+        legacy: None,
+        layout: Some(1),
+    };
+    assert_eq!(opt.min_version(), Some(6));
+
+    let opt = Opt {
+        theme: None,
+        legacy: Some(true),
+        layout: None,
+    };
+    assert_eq!(opt.min_version(), Some(0));
+}
@@ -0,0 +1,121 @@
+#![allow(unexpected_cfgs)]
+
+use optfield::optfield;
+
+#[test]
+fn views_as_ref_and_as_mut() {
+    #[optfield(Opt, from, views)]
+    struct Original {
+        text: String,
+        tags: Vec<String>,
+        number: Option<i32>,
+    }
+
+    let mut opt = Opt::from(Original {
+        text: "text".to_string(),
+        tags: vec!["tag".to_string()],
+        number: None,
+    });
+
+    let view = opt.as_ref();
+    let copy = view;
+    assert_eq!(view.text.map(String::as_str), Some("text"));
+    assert_eq!(copy.tags.map(Vec::len), Some(1));
+    assert_eq!(view.number, None);
+
+    let view = opt.as_mut();
+    view.text.unwrap().push_str(" changed");
+    view.tags.unwrap().clear();
+    assert!(view.number.is_none());
+
+    assert_eq!(opt.text.unwrap(), "text changed");
+    assert_eq!(opt.tags.unwrap(), Vec::<String>::new());
+}
+
+#[test]
+fn views_select() {
+    #[optfield(Opt, from, views)]
+    struct Original {
+        text: String,
+        number: Option<i32>,
+        flag: bool,
+    }
+
+    let original = Original {
+        text: "text".to_string(),
+        number: Some(1),
+        flag: true,
+    };
+
+    let view = original.select(&["number", "text", "unknown"]);
+
+    assert_eq!(view.text, Some(&original.text));
+    assert_eq!(view.number, Some(&1));
+    assert_eq!(view.flag, None);
+
+    let view = original.select(&[]);
+
+    assert!(view.text.is_none() && view.number.is_none() && view.flag.is_none());
+
+    let mut opt = Opt::from(original);
+    assert_eq!(opt.as_mut().flag, Some(&mut true));
+}
+
+#[test]
+fn views_rewrap() {
+    #[optfield(Opt, rewrap, views)]
+    struct Original {
+        number: Option<i32>,
+    }
+
+    let original = Original { number: None };
+
+    let view = original.select(&["number"]);
+    assert_eq!(view.number, Some(&None));
+
+    let mut opt = Opt { number: Some(None) };
+    assert_eq!(opt.as_ref().number, Some(&None));
+
+    *opt.as_mut().number.unwrap() = Some(1);
+    assert_eq!(opt.number, Some(Some(1)));
+}
+
+#[test]
+fn views_generic_tuple_struct() {
+    #[optfield(Opt, from, views)]
+    struct Original<'a, T>(&'a T, u8)
+    where
+        T: PartialEq;
+
+    let value = "value".to_string();
+    let original = Original(&value, 1);
+
+    let view = original.select(&["0"]);
+    assert_eq!(view.0, Some(&&value));
+    assert_eq!(view.1, None);
+
+    let mut opt = Opt::from(original);
+    *opt.as_mut().1.unwrap() = 2;
+    assert_eq!(opt.as_ref().1, Some(&2));
+}
+
+#[test]
+fn views_cfg_field() {
+    #[optfield(Opt, from, views, field_attrs)]
+    struct Original {
+        text: String,
+        #[cfg(any())]
+        hidden: String,
+    }
+
+    let mut opt = Opt::from(Original {
+        text: "text".to_string(),
+        #[cfg(any())]
+        hidden: "hidden".to_string(),
+    });
+
+    assert_eq!(opt.as_ref().text.unwrap(), "text");
+
+    opt.as_mut().text.unwrap().push('!');
+    assert_eq!(opt.text.unwrap(), "text!");
+}